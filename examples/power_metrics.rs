@@ -0,0 +1,91 @@
+// 以 Prometheus 文本格式导出当前电源状态的示例
+//
+// 运行: cargo run --example power_metrics -- [监听地址，默认 0.0.0.0:9184]
+// 抓取: curl http://<主机>:9184/metrics
+// 读数来自 PowerMonitor::current()，由观察者在后台实时更新；每次抓取只读取缓存，不会查询系统
+
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::Duration;
+
+use device_aware::{PowerMonitor, PowerMonitorBuilder};
+
+fn metrics_text(monitor: &PowerMonitor) -> String {
+    let state = monitor.current();
+    let mut out = String::new();
+    let _ = writeln!(out, "# HELP device_power_source Power source: 0 = AC, 1 = battery, 2 = short term, -1 = unknown.");
+    let _ = writeln!(out, "# TYPE device_power_source gauge");
+    let _ = writeln!(out, "device_power_source{{source=\"{}\"}} {}", state.source.key(), state.source.as_i32());
+    let _ = writeln!(out, "# HELP device_battery_saver Whether battery saver is on.");
+    let _ = writeln!(out, "# TYPE device_battery_saver gauge");
+    let _ = writeln!(out, "device_battery_saver {}", u8::from(state.saver_on));
+    // 尚未收到滑块位置的推送时不输出该指标
+    if let Some(mode) = state.effective_mode {
+        let _ = writeln!(out, "# HELP device_effective_mode Power mode slider position: 0 = battery saver ... 5 = game mode.");
+        let _ = writeln!(out, "# TYPE device_effective_mode gauge");
+        let _ = writeln!(out, "device_effective_mode{{mode=\"{}\"}} {}", mode.key(), mode.as_u8());
+    }
+    // 尚未取得电量读数 (例如台式机) 时不输出该指标
+    if let Some(percent) = state.battery_percent {
+        let _ = writeln!(out, "# HELP device_battery_percent Remaining battery capacity in percent.");
+        let _ = writeln!(out, "# TYPE device_battery_percent gauge");
+        let _ = writeln!(out, "device_battery_percent {}", percent);
+    }
+    out
+}
+
+fn serve(mut stream: TcpStream, monitor: &PowerMonitor) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(1)))?;
+    stream.set_write_timeout(Some(Duration::from_secs(1)))?;
+
+    // 只看请求行，其余请求头读掉即可
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let path = request_line.split_whitespace().nth(1).unwrap_or("");
+    let response = if path == "/metrics" {
+        let body = metrics_text(monitor);
+        format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        )
+    } else {
+        "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string()
+    };
+    stream.write_all(response.as_bytes())
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let addr = std::env::args().nth(1).unwrap_or_else(|| "0.0.0.0:9184".to_string());
+
+    // 事件本身不需要处理，current() 的缓存由监控器内部维护
+    let monitor = PowerMonitorBuilder::new()
+        .with_effective_mode()
+        .with_power_source()
+        .with_saver_status()
+        .with_battery_percent()
+        .build(|_| {})?;
+
+    let listener = TcpListener::bind(&addr)?;
+    println!("指标地址 http://{}/metrics", addr);
+
+    // 抓取频率很低，逐个连接同步处理即可
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                if let Err(e) = serve(stream, &monitor) {
+                    eprintln!("处理请求失败: {}", e);
+                }
+            }
+            Err(e) => eprintln!("接受连接失败: {}", e),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,50 @@
+// 通过 TCP 把电源事件转发给远程面板的示例
+//
+// 运行: cargo run --example power_net --features serde -- [监听地址，默认 0.0.0.0:7878]
+// 客户端: nc <主机> 7878
+// 每个事件一行 JSON (NDJSON)，格式与 DeviceAware --json 相同；允许多个客户端同时连接，
+// 新连接只接收之后发生的事件，写入失败的连接会被移除
+
+use std::io::Write;
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use device_aware::{replay, PowerMonitorBuilder};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let addr = std::env::args().nth(1).unwrap_or_else(|| "0.0.0.0:7878".to_string());
+    let listener = TcpListener::bind(&addr)?;
+    println!("监听 {}，等待客户端连接...", addr);
+
+    let clients: Arc<Mutex<Vec<TcpStream>>> = Arc::new(Mutex::new(Vec::new()));
+
+    let c = clients.clone();
+    let _monitor = PowerMonitorBuilder::new()
+        .with_effective_mode()
+        .with_power_source()
+        .with_saver_status()
+        .with_battery_percent()
+        .build(move |event| {
+            // 与录制文件同一个编码器，每个事件一行
+            let line = replay::event_to_json(SystemTime::now(), event) + "\n";
+            let mut clients = c.lock().unwrap_or_else(|e| e.into_inner());
+            // 写入失败说明客户端已断开，直接移除
+            clients.retain_mut(|stream| stream.write_all(line.as_bytes()).is_ok());
+        })?;
+
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                if let Ok(peer) = stream.peer_addr() {
+                    println!("客户端已连接: {}", peer);
+                }
+                // 写入超时视为断开，避免慢客户端长时间阻塞回调线程
+                let _ = stream.set_write_timeout(Some(Duration::from_secs(1)));
+                clients.lock().unwrap_or_else(|e| e.into_inner()).push(stream);
+            }
+            Err(e) => eprintln!("接受连接失败: {}", e),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,124 @@
+// 以 Windows 服务方式运行电源监控的示例
+//
+// 安装 / 启动 (管理员权限):
+//   sc create DeviceAwareService binPath= "<path>\power_service.exe"
+//   sc start DeviceAwareService
+// 事件写入 %TEMP%\DeviceAwareService.log (服务没有控制台)
+
+#[cfg(all(windows, not(feature = "mock")))]
+mod imp {
+    use std::ffi::c_void;
+    use std::fs::OpenOptions;
+    use std::io::Write;
+    use std::sync::{Condvar, Mutex};
+
+    use device_aware::service::{power_event_from_control, ServicePowerNotifications};
+    use device_aware::{PowerEvent, PowerMonitorBuilder};
+    use windows::core::{w, Result, PCWSTR, PWSTR};
+    use windows::Win32::Foundation::{ERROR_CALL_NOT_IMPLEMENTED, NO_ERROR};
+    use windows::Win32::System::Services::{
+        RegisterServiceCtrlHandlerExW, SetServiceStatus, StartServiceCtrlDispatcherW,
+        SERVICE_ACCEPT_POWEREVENT, SERVICE_ACCEPT_SHUTDOWN, SERVICE_ACCEPT_STOP,
+        SERVICE_CONTROL_INTERROGATE, SERVICE_CONTROL_POWEREVENT, SERVICE_CONTROL_SHUTDOWN,
+        SERVICE_CONTROL_STOP, SERVICE_RUNNING, SERVICE_START_PENDING, SERVICE_STATUS,
+        SERVICE_STATUS_CURRENT_STATE, SERVICE_STATUS_HANDLE, SERVICE_STOPPED,
+        SERVICE_STOP_PENDING, SERVICE_TABLE_ENTRYW, SERVICE_WIN32_OWN_PROCESS,
+    };
+
+    const SERVICE_NAME: PCWSTR = w!("DeviceAwareService");
+
+    // 控制处理函数通知 run() 退出
+    static STOP: (Mutex<bool>, Condvar) = (Mutex::new(false), Condvar::new());
+
+    fn write_event(event: PowerEvent) {
+        let path = std::env::temp_dir().join("DeviceAwareService.log");
+        if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(path) {
+            let _ = writeln!(file, "{:?}", event);
+        }
+    }
+
+    fn set_state(handle: SERVICE_STATUS_HANDLE, state: SERVICE_STATUS_CURRENT_STATE, accepted: u32) -> Result<()> {
+        let status = SERVICE_STATUS {
+            dwServiceType: SERVICE_WIN32_OWN_PROCESS,
+            dwCurrentState: state,
+            dwControlsAccepted: accepted,
+            ..Default::default()
+        };
+        unsafe { SetServiceStatus(handle, &status) }
+    }
+
+    unsafe extern "system" fn control_handler(
+        control: u32,
+        event_type: u32,
+        event_data: *mut c_void,
+        _context: *mut c_void,
+    ) -> u32 {
+        match control {
+            SERVICE_CONTROL_STOP | SERVICE_CONTROL_SHUTDOWN => {
+                *STOP.0.lock().unwrap_or_else(|e| e.into_inner()) = true;
+                STOP.1.notify_all();
+                NO_ERROR.0
+            }
+            SERVICE_CONTROL_POWEREVENT => {
+                // 以服务句柄注册的电源设置通知从这里进来
+                if let Some(event) = unsafe { power_event_from_control(control, event_type, event_data) } {
+                    write_event(event);
+                }
+                NO_ERROR.0
+            }
+            SERVICE_CONTROL_INTERROGATE => NO_ERROR.0,
+            _ => ERROR_CALL_NOT_IMPLEMENTED.0,
+        }
+    }
+
+    fn run() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let handle = unsafe { RegisterServiceCtrlHandlerExW(SERVICE_NAME, Some(control_handler), None)? };
+        set_state(handle, SERVICE_START_PENDING, 0)?;
+
+        // 滑块位置只能通过回调订阅；其余设置改为以服务句柄注册，经 HandlerEx 投递
+        let monitor = PowerMonitorBuilder::new().with_effective_mode().build(write_event)?;
+        let notifications = ServicePowerNotifications::for_monitor(handle)?;
+
+        set_state(handle, SERVICE_RUNNING, SERVICE_ACCEPT_STOP | SERVICE_ACCEPT_SHUTDOWN | SERVICE_ACCEPT_POWEREVENT)?;
+
+        let stopped = STOP.0.lock().unwrap_or_else(|e| e.into_inner());
+        drop(STOP.1.wait_while(stopped, |stop| !*stop).unwrap_or_else(|e| e.into_inner()));
+
+        set_state(handle, SERVICE_STOP_PENDING, 0)?;
+        drop(notifications);
+        drop(monitor);
+        set_state(handle, SERVICE_STOPPED, 0)?;
+        Ok(())
+    }
+
+    unsafe extern "system" fn service_main(_argc: u32, _argv: *mut PWSTR) {
+        if let Err(e) = run() {
+            let path = std::env::temp_dir().join("DeviceAwareService.log");
+            if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(path) {
+                let _ = writeln!(file, "service failed: {:?}", e);
+            }
+        }
+    }
+
+    pub fn main() -> Result<()> {
+        let table = [
+            SERVICE_TABLE_ENTRYW {
+                lpServiceName: PWSTR(SERVICE_NAME.as_ptr() as *mut u16),
+                lpServiceProc: Some(service_main),
+            },
+            SERVICE_TABLE_ENTRYW::default(),
+        ];
+        // 阻塞直到服务停止；直接在控制台运行会失败 (ERROR_FAILED_SERVICE_CONTROLLER_CONNECT)
+        unsafe { StartServiceCtrlDispatcherW(table.as_ptr()) }
+    }
+}
+
+#[cfg(all(windows, not(feature = "mock")))]
+fn main() -> windows::core::Result<()> {
+    imp::main()
+}
+
+#[cfg(not(all(windows, not(feature = "mock"))))]
+fn main() {
+    eprintln!("power_service 示例只能在 Windows 上以真实后端运行");
+}
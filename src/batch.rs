@@ -0,0 +1,51 @@
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+// 后台线程收集 window 内陆续到达的事件，合并成一个 Vec 调用一次 deliver。
+// 批次从第一个事件到达时开始计时；所有发送端丢弃后先投递剩余事件，再结束线程
+pub(crate) struct Batcher {
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Batcher {
+    pub(crate) fn spawn<T, F>(window: Duration, deliver: F) -> (Self, Sender<T>)
+    where
+        T: Send + 'static,
+        F: Fn(Vec<T>) + Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel::<T>();
+        let thread = thread::Builder::new()
+            .name("device-aware-batch".to_string())
+            .spawn(move || {
+                while let Ok(first) = rx.recv() {
+                    let deadline = Instant::now() + window;
+                    let mut batch = vec![first];
+                    let disconnected = loop {
+                        match rx.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                            Ok(item) => batch.push(item),
+                            Err(RecvTimeoutError::Timeout) => break false,
+                            Err(RecvTimeoutError::Disconnected) => break true,
+                        }
+                    };
+                    deliver(batch);
+                    if disconnected {
+                        break;
+                    }
+                }
+            })
+            .inspect_err(|e| log_error!("Batcher: failed to spawn thread: {:?}", e))
+            .ok();
+
+        (Batcher { thread }, tx)
+    }
+}
+
+impl Drop for Batcher {
+    // 调用方需先丢弃所有发送端，否则这里会一直等待
+    fn drop(&mut self) {
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
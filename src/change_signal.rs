@@ -0,0 +1,56 @@
+use std::sync::{Condvar, Mutex};
+use std::time::Duration;
+
+// 供 wait_for_change 使用: 回调每收到一个 u32 就递增序号并唤醒等待者
+#[derive(Default)]
+pub(crate) struct ChangeSignal {
+    // (序号, 最近一次的值)
+    latest: Mutex<(u64, u32)>,
+    cond: Condvar,
+}
+
+impl ChangeSignal {
+    pub(crate) fn notify(&self, val: u32) {
+        let mut latest = self.latest.lock().unwrap_or_else(|e| e.into_inner());
+        latest.0 = latest.0.wrapping_add(1);
+        latest.1 = val;
+        self.cond.notify_all();
+    }
+
+    pub(crate) fn wait(&self, timeout: Duration) -> Option<u32> {
+        let latest = self.latest.lock().unwrap_or_else(|e| e.into_inner());
+        let seq = latest.0;
+        let (latest, result) = self
+            .cond
+            .wait_timeout_while(latest, timeout, |latest| latest.0 == seq)
+            .unwrap_or_else(|e| e.into_inner());
+        if result.timed_out() { None } else { Some(latest.1) }
+    }
+}
+
+// 供 verify_notifications 使用: 记录是否收到过任何通知 (不论载荷与暂停状态)
+#[derive(Default)]
+pub(crate) struct ArrivalSignal {
+    arrived: Mutex<bool>,
+    cond: Condvar,
+}
+
+impl ArrivalSignal {
+    pub(crate) fn set(&self) {
+        let mut arrived = self.arrived.lock().unwrap_or_else(|e| e.into_inner());
+        if !*arrived {
+            *arrived = true;
+            self.cond.notify_all();
+        }
+    }
+
+    // 已经收到过通知时立即返回 true，否则最多等待 timeout
+    pub(crate) fn wait(&self, timeout: Duration) -> bool {
+        let arrived = self.arrived.lock().unwrap_or_else(|e| e.into_inner());
+        let (arrived, _) = self
+            .cond
+            .wait_timeout_while(arrived, timeout, |arrived| !*arrived)
+            .unwrap_or_else(|e| e.into_inner());
+        *arrived
+    }
+}
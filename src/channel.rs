@@ -0,0 +1,70 @@
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use windows_core::GUID;
+
+use crate::{DeviceAwareError, EffectiveModeObserver, EffectivePowerMode, PowerSettingObserver};
+
+// ============================================================================
+// 阻塞式消费: 观察者与接收端放在一起，适合脚本式的同步程序
+//     for mode in channel.iter() { ... }
+// iter() 阻塞等待下一个事件；close() 注销观察者后，已排队的事件取完迭代即结束
+// ============================================================================
+
+pub struct EffectiveModeChannel {
+    observer: Mutex<Option<EffectiveModeObserver>>,
+    rx: Receiver<EffectivePowerMode>,
+}
+
+impl EffectiveModeChannel {
+    pub fn new() -> Result<Self, DeviceAwareError> {
+        let (observer, rx) = EffectiveModeObserver::new_channel()?;
+        Ok(EffectiveModeChannel { observer: Mutex::new(Some(observer)), rx })
+    }
+
+    pub fn iter(&self) -> mpsc::Iter<'_, EffectivePowerMode> {
+        self.rx.iter()
+    }
+
+    // 超时或已关闭时返回 None
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<EffectivePowerMode> {
+        self.rx.recv_timeout(timeout).ok()
+    }
+
+    // 注销观察者: 回调 (连同发送端) 随之释放，iter() 在取完剩余事件后结束。可在循环体内调用
+    pub fn close(&self) {
+        let observer = self.observer.lock().unwrap_or_else(|e| e.into_inner()).take();
+        drop(observer);
+    }
+}
+
+pub struct PowerSettingChannel {
+    guid: GUID,
+    observer: Mutex<Option<PowerSettingObserver>>,
+    rx: Receiver<u32>,
+}
+
+impl PowerSettingChannel {
+    pub fn new(guid: GUID) -> Result<Self, DeviceAwareError> {
+        let (observer, rx) = PowerSettingObserver::new_channel(guid)?;
+        Ok(PowerSettingChannel { guid, observer: Mutex::new(Some(observer)), rx })
+    }
+
+    pub fn guid(&self) -> GUID {
+        self.guid
+    }
+
+    pub fn iter(&self) -> mpsc::Iter<'_, u32> {
+        self.rx.iter()
+    }
+
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<u32> {
+        self.rx.recv_timeout(timeout).ok()
+    }
+
+    pub fn close(&self) {
+        let observer = self.observer.lock().unwrap_or_else(|e| e.into_inner()).take();
+        drop(observer);
+    }
+}
@@ -0,0 +1,180 @@
+use std::ffi::c_void;
+
+// ============================================================================
+// OwnedContext: 回调方式注册 (DEVICE_NOTIFY_CALLBACK、CM_Register_Notification 等) 共用的
+// 上下文所有权。回调对象装箱后以瘦指针 (void*) 交给系统，系统在每次通知时原样交回 (Double Boxing)。
+// 唯一一次 Box::into_raw 在 register_with 中，唯一一次 Box::from_raw 在 release 中:
+// - 注册失败时上下文随 register_with 返回的错误一起释放 (回滚)
+// - 注册成功后由观察者持有，注销之后 release (或 Drop)；释放后指针置空，重复 release 是空操作
+// - take 把所有权整体移交给其他线程，原对象变为空
+// 本类型不依赖 Win32，便于单独测试；何时可以释放 (即系统不再回调) 仍由各观察者保证
+// ============================================================================
+
+pub(crate) struct OwnedContext<T> {
+    ptr: *mut T,
+}
+
+impl<T> OwnedContext<T> {
+    // 分配上下文并把指针交给 register；register 失败时立即释放上下文并返回错误
+    pub(crate) fn register_with<R, E>(
+        value: T,
+        register: impl FnOnce(*const c_void) -> Result<R, E>,
+    ) -> Result<(Self, R), E> {
+        let context = OwnedContext { ptr: Box::into_raw(Box::new(value)) };
+        // 失败时 context 在这里离开作用域，经 Drop 回滚
+        let registered = register(context.as_ptr())?;
+        Ok((context, registered))
+    }
+
+    // 窗口 / 服务句柄注册没有回调上下文
+    pub(crate) fn empty() -> Self {
+        OwnedContext { ptr: std::ptr::null_mut() }
+    }
+
+    // 交给系统的 Context 值；释放之后为空指针
+    pub(crate) fn as_ptr(&self) -> *const c_void {
+        self.ptr as *const c_void
+    }
+
+    // 取走所有权，原对象变为空 (之后对它 release / Drop 都是空操作)
+    pub(crate) fn take(&mut self) -> Self {
+        OwnedContext { ptr: std::mem::replace(&mut self.ptr, std::ptr::null_mut()) }
+    }
+
+    // 释放上下文并置空；调用方必须先确保系统不会再用该指针回调
+    pub(crate) fn release(&mut self) {
+        if !self.ptr.is_null() {
+            unsafe { drop(Box::from_raw(self.ptr)) };
+            self.ptr = std::ptr::null_mut();
+        }
+    }
+
+    /// 把系统交回的 Context 还原为回调对象的引用
+    ///
+    /// # Safety
+    /// context 必须是某个尚未释放的 OwnedContext<T> 的 as_ptr()，且在返回的引用使用期间不会被释放
+    pub(crate) unsafe fn get<'a>(context: *const c_void) -> &'a T {
+        unsafe { &*(context as *const T) }
+    }
+}
+
+impl<T> Drop for OwnedContext<T> {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+// SAFETY: OwnedContext 独占其指向的 T，语义上与 Box<T> 相同
+unsafe impl<T: Send> Send for OwnedContext<T> {}
+// SAFETY: &self 方法只读取指针值，不会访问 T
+unsafe impl<T: Sync> Sync for OwnedContext<T> {}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use super::*;
+
+    // 随上下文一起分配的守卫，析构时计数，用来确认每个上下文恰好释放一次
+    struct DropCounter(Arc<AtomicUsize>);
+
+    impl Drop for DropCounter {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    type Context = (usize, DropCounter);
+
+    fn register(id: usize, drops: &Arc<AtomicUsize>) -> OwnedContext<Context> {
+        let (context, ptr) =
+            OwnedContext::register_with((id, DropCounter(drops.clone())), Ok::<_, ()>).unwrap();
+        assert_eq!(ptr, context.as_ptr());
+        context
+    }
+
+    #[test]
+    fn registered_context_is_freed_once_on_drop() {
+        let drops = Arc::new(AtomicUsize::new(0));
+        let context = register(7, &drops);
+        assert_eq!(unsafe { OwnedContext::<Context>::get(context.as_ptr()) }.0, 7);
+        assert_eq!(drops.load(Ordering::SeqCst), 0);
+        drop(context);
+        assert_eq!(drops.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn failed_registration_rolls_back_once() {
+        let drops = Arc::new(AtomicUsize::new(0));
+        let result = OwnedContext::register_with((0, DropCounter(drops.clone())), |ptr| {
+            assert!(!ptr.is_null());
+            Err::<(), _>("registration failed")
+        });
+        assert!(matches!(result, Err("registration failed")));
+        assert_eq!(drops.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn empty_context_has_nothing_to_free() {
+        let mut context = OwnedContext::<Context>::empty();
+        assert!(context.as_ptr().is_null());
+        context.release();
+    }
+
+    // unsubscribe 之后再次 unsubscribe 与 Drop 走的都是 release 的空指针守卫: 只释放一次
+    #[test]
+    fn repeated_release_then_drop_frees_once() {
+        let drops = Arc::new(AtomicUsize::new(0));
+        let mut context = register(3, &drops);
+        context.release();
+        assert!(context.as_ptr().is_null());
+        assert_eq!(drops.load(Ordering::SeqCst), 1);
+        context.release();
+        drop(context);
+        assert_eq!(drops.load(Ordering::SeqCst), 1);
+    }
+
+    // 释放交给其他线程: 原对象变为空，由接手的一方释放
+    #[test]
+    fn taken_context_is_freed_by_new_owner() {
+        let drops = Arc::new(AtomicUsize::new(0));
+        let mut context = register(1, &drops);
+        let taken = context.take();
+        assert!(context.as_ptr().is_null());
+        drop(context);
+        assert_eq!(drops.load(Ordering::SeqCst), 0);
+        std::thread::spawn(move || drop(taken)).join().unwrap();
+        assert_eq!(drops.load(Ordering::SeqCst), 1);
+    }
+
+    // 同一 GUID 注册多次时每个观察者各自分配上下文: 指针互不相同，交错释放时每个恰好释放一次
+    #[test]
+    fn interleaved_contexts_are_freed_exactly_once() {
+        let drops: Vec<_> = (0..4).map(|_| Arc::new(AtomicUsize::new(0))).collect();
+        let mut contexts: Vec<_> = drops.iter().enumerate().map(|(id, d)| register(id, d)).collect();
+        for (i, a) in contexts.iter().enumerate() {
+            assert_eq!(unsafe { OwnedContext::<Context>::get(a.as_ptr()) }.0, i);
+            for b in &contexts[i + 1..] {
+                assert_ne!(a.as_ptr(), b.as_ptr());
+            }
+        }
+
+        for (step, index) in [2, 0, 3, 1].into_iter().enumerate() {
+            contexts[index].release();
+            assert_eq!(drops[index].load(Ordering::SeqCst), 1);
+            let freed = drops.iter().filter(|d| d.load(Ordering::SeqCst) == 1).count();
+            assert_eq!(freed, step + 1);
+            // 尚未释放的上下文仍指向各自的回调对象
+            for (id, survivor) in contexts.iter().enumerate() {
+                if drops[id].load(Ordering::SeqCst) == 0 {
+                    assert_eq!(unsafe { OwnedContext::<Context>::get(survivor.as_ptr()) }.0, id);
+                }
+            }
+        }
+        drop(contexts);
+        for d in &drops {
+            assert_eq!(d.load(Ordering::SeqCst), 1);
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use std::ffi::c_void;
+
+use windows_core::{Error, GUID};
+use windows::Win32::Devices::DeviceAndDriverInstallation::{
+    CM_MapCrToWin32Err, CM_Register_Notification, CM_Unregister_Notification,
+    CM_NOTIFY_ACTION, CM_NOTIFY_ACTION_DEVICEINTERFACEARRIVAL, CM_NOTIFY_ACTION_DEVICEINTERFACEREMOVAL,
+    CM_NOTIFY_EVENT_DATA, CM_NOTIFY_FILTER, CM_NOTIFY_FILTER_0, CM_NOTIFY_FILTER_0_2,
+    CM_NOTIFY_FILTER_TYPE_DEVICEINTERFACE, CR_SUCCESS, HCMNOTIFICATION,
+};
+use windows::Win32::Foundation::{ERROR_GEN_FAILURE, WIN32_ERROR};
+
+use crate::context::OwnedContext;
+use crate::panic_guard::guard_callback;
+use crate::reentrancy::{defer_release, enter_callback, is_in_callback, release_when_idle};
+use crate::{DeviceAwareError, DeviceEvent};
+
+// ============================================================================
+// 4. DeviceNotifyObserver: 设备接口到达 / 移除通知
+// RegisterDeviceNotification 只能投递到窗口或服务句柄，不支持 DEVICE_NOTIFY_CALLBACK；
+// 回调方式的等价接口是 CM_Register_Notification，这里用它实现，
+// 内存模型与 SuspendResumeObserver 相同的 Double Boxing + Drop 注销
+// ============================================================================
+
+pub type DeviceEventCallback = Box<dyn Fn(DeviceEvent) + Send + Sync>;
+
+// 内存所有权约定与 PowerSettingObserver 相同: 上下文在注册失败时立即回滚，
+// 否则只在 unsubscribe 注销之后释放一次。
+// CM_Unregister_Notification 会等待正在执行的回调返回，因此在回调内部 unsubscribe 时
+// 注销与释放交给独立线程完成
+pub struct DeviceNotifyObserver {
+    interface_guid: GUID,
+    handle: Option<HCMNOTIFICATION>,
+    context: OwnedContext<DeviceEventCallback>,
+}
+
+impl DeviceNotifyObserver {
+    // 订阅某个设备接口类 (例如 guids::GUID_DEVINTERFACE_USB_DEVICE) 的到达与移除
+    pub fn new<F>(interface_guid: GUID, handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(DeviceEvent) + Send + Sync + 'static
+    {
+        let callback: DeviceEventCallback = Box::new(handler);
+
+        // 与 DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS 一样，filter 只需在注册调用期间有效
+        let filter = CM_NOTIFY_FILTER {
+            cbSize: std::mem::size_of::<CM_NOTIFY_FILTER>() as u32,
+            FilterType: CM_NOTIFY_FILTER_TYPE_DEVICEINTERFACE,
+            u: CM_NOTIFY_FILTER_0 {
+                DeviceInterface: CM_NOTIFY_FILTER_0_2 { ClassGuid: interface_guid },
+            },
+            ..Default::default()
+        };
+
+        // 注册失败时由 OwnedContext 回收内存，防止泄漏
+        let registered = OwnedContext::register_with(callback, |context| {
+            let mut handle = HCMNOTIFICATION::default();
+            let result = unsafe {
+                CM_Register_Notification(&filter, Some(context), Some(Self::static_callback), &mut handle)
+            };
+            if result == CR_SUCCESS { Ok(handle) } else { Err(result) }
+        });
+
+        let (context, handle) = match registered {
+            Ok(registered) => registered,
+            Err(result) => {
+                let code = unsafe { CM_MapCrToWin32Err(result, ERROR_GEN_FAILURE.0) };
+                let error = Error::from(WIN32_ERROR(code).to_hresult());
+                log_error!("DeviceNotifyObserver: CM_Register_Notification failed for {:?}: {:?}", interface_guid, result);
+                return Err(DeviceAwareError::Registration {
+                    guid: Some(interface_guid),
+                    api: "CM_Register_Notification",
+                    error,
+                });
+            }
+        };
+        log_debug!("DeviceNotifyObserver: registered device interface {:?}", interface_guid);
+
+        Ok(DeviceNotifyObserver {
+            interface_guid,
+            handle: Some(handle),
+            context,
+        })
+    }
+
+    // 该观察者订阅的设备接口类 GUID
+    pub fn interface_guid(&self) -> GUID {
+        self.interface_guid
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.handle.is_some()
+    }
+
+    // 主动注销通知并释放回调；之后 Drop 不再做任何事，重复调用也是安全的
+    pub fn unsubscribe(&mut self) {
+        if is_in_callback(self.context.as_ptr())
+            && let Some(handle) = self.handle.take()
+        {
+            let handle = handle.0 as usize;
+            let context = self.context.take();
+            defer_release("DeviceNotifyObserver", context, move || {
+                unsafe {
+                    let _ = CM_Unregister_Notification(HCMNOTIFICATION(handle as *mut c_void));
+                }
+            });
+            return;
+        }
+        if let Some(handle) = self.handle.take() {
+            log_debug!("DeviceNotifyObserver: unregistering device interface {:?}", self.interface_guid);
+            unsafe {
+                let _ = CM_Unregister_Notification(handle);
+            }
+        }
+        // 等其他线程上仍在执行的回调返回后释放，随即置空，防止重复释放
+        release_when_idle(&mut self.context);
+    }
+
+    // 只转发接口到达与移除，其余动作 (查询移除等) 属于设备句柄过滤器，这里不会收到
+    unsafe extern "system" fn static_callback(
+        _hnotify: HCMNOTIFICATION,
+        context: *const c_void,
+        action: CM_NOTIFY_ACTION,
+        event_data: *const CM_NOTIFY_EVENT_DATA,
+        event_data_size: u32,
+    ) -> u32 {
+        if context.is_null() || event_data.is_null() {
+            return 0;
+        }
+        let symbolic_link = unsafe { symbolic_link(event_data, event_data_size) };
+        let event = match action {
+            CM_NOTIFY_ACTION_DEVICEINTERFACEARRIVAL => DeviceEvent::Arrival(symbolic_link),
+            CM_NOTIFY_ACTION_DEVICEINTERFACEREMOVAL => DeviceEvent::Removal(symbolic_link),
+            _ => return 0,
+        };
+        log_trace!("DeviceNotifyObserver: {:?}", event);
+        let callback = unsafe { OwnedContext::<DeviceEventCallback>::get(context) };
+        enter_callback(context, || {
+            guard_callback("DeviceNotifyObserver", || callback(event));
+        });
+        0
+    }
+}
+
+// 读取 CM_NOTIFY_EVENT_DATA 末尾以 NUL 结尾的 UTF-16 接口路径，长度以 event_data_size 为界
+unsafe fn symbolic_link(event_data: *const CM_NOTIFY_EVENT_DATA, event_data_size: u32) -> String {
+    let start = unsafe { std::ptr::addr_of!((*event_data).u.DeviceInterface.SymbolicLink) } as *const u16;
+    let offset = start as usize - event_data as usize;
+    let max_len = (event_data_size as usize).saturating_sub(offset) / 2;
+    let chars = unsafe { std::slice::from_raw_parts(start, max_len) };
+    let len = chars.iter().position(|&c| c == 0).unwrap_or(max_len);
+    String::from_utf16_lossy(&chars[..len])
+}
+
+// SAFETY: 与 PowerSettingObserver 相同，上下文只由系统回调以只读方式使用，
+// 仅在 unsubscribe (需要 &mut self) 或 Drop 中释放一次
+unsafe impl Send for DeviceNotifyObserver {}
+// SAFETY: &self 方法只读取 interface_guid 与 handle 是否存在，不会触碰上下文
+unsafe impl Sync for DeviceNotifyObserver {}
+
+impl Drop for DeviceNotifyObserver {
+    fn drop(&mut self) {
+        self.unsubscribe();
+    }
+}
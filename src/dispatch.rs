@@ -0,0 +1,38 @@
+use std::sync::mpsc::{self, Sender};
+use std::thread::{self, JoinHandle};
+
+// 单个后台线程按发送顺序逐个调用 deliver，保证回调全序且始终在同一线程上执行。
+// 所有发送端丢弃后处理完队列中剩余的事件，再结束线程
+pub(crate) struct Dispatcher {
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Dispatcher {
+    pub(crate) fn spawn<T, F>(deliver: F) -> (Self, Sender<T>)
+    where
+        T: Send + 'static,
+        F: Fn(T) + Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel::<T>();
+        let thread = thread::Builder::new()
+            .name("device-aware-dispatch".to_string())
+            .spawn(move || {
+                for item in rx {
+                    deliver(item);
+                }
+            })
+            .inspect_err(|e| log_error!("Dispatcher: failed to spawn thread: {:?}", e))
+            .ok();
+
+        (Dispatcher { thread }, tx)
+    }
+}
+
+impl Drop for Dispatcher {
+    // 调用方需先丢弃所有发送端，否则这里会一直等待
+    fn drop(&mut self) {
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
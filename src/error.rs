@@ -0,0 +1,44 @@
+use std::fmt;
+
+use windows_core::{Error, GUID};
+
+// ============================================================================
+// 对外统一的错误类型: 调用方无需直接依赖 windows crate 即可匹配错误
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeviceAwareError {
+    // 注册通知失败；guid 为 None 表示不针对某个电源设置的注册 (滑块位置、睡眠/唤醒)，
+    // api 为失败的 Win32 函数名
+    Registration { guid: Option<GUID>, api: &'static str, error: Error },
+    // 当前系统版本或平台不支持该功能
+    Unsupported,
+    // 同步查询当前状态失败
+    Query(Error),
+}
+
+// Display 只描述失败的操作，底层的 windows::core::Error (含 HRESULT) 通过 source() 取得，
+// anyhow / eyre 等会沿 source 链完整打印，不会重复
+impl fmt::Display for DeviceAwareError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeviceAwareError::Registration { guid: Some(guid), api, .. } => {
+                write!(f, "{} failed to register notification for GUID {:?}", api, guid)
+            }
+            DeviceAwareError::Registration { guid: None, api, .. } => {
+                write!(f, "{} failed to register notification", api)
+            }
+            DeviceAwareError::Unsupported => f.write_str("not supported on this system"),
+            DeviceAwareError::Query(_) => f.write_str("failed to query power status"),
+        }
+    }
+}
+
+impl std::error::Error for DeviceAwareError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DeviceAwareError::Registration { error, .. } | DeviceAwareError::Query(error) => Some(error),
+            DeviceAwareError::Unsupported => None,
+        }
+    }
+}
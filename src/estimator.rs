@@ -0,0 +1,164 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use crate::guids::GUID_BATTERY_PERCENTAGE_REMAINING;
+use crate::{AcDcObserver, DeviceAwareError, PowerSettingObserver, PowerSourceType};
+
+// ============================================================================
+// BatteryEstimator: 根据近期电量变化线性估算剩余续航，不依赖系统的 BatteryLifeTime
+// ============================================================================
+
+// 默认只用最近 30 分钟的电量样本计算放电速率
+const DEFAULT_WINDOW: Duration = Duration::from_secs(30 * 60);
+
+struct EstimatorState {
+    window: Duration,
+    samples: VecDeque<(SystemTime, u32)>,
+    // None 表示尚未收到 AC/DC 通知，此时按电池供电处理
+    on_battery: Option<bool>,
+}
+
+impl EstimatorState {
+    fn record_percent(&mut self, at: SystemTime, percent: u32) {
+        if self.on_battery == Some(false) {
+            return;
+        }
+        // 电量回升说明在充电，之前的斜率已经没有意义
+        if self.samples.back().is_some_and(|&(_, last)| percent > last) {
+            self.samples.clear();
+        }
+        self.samples.push_back((at, percent));
+        while self
+            .samples
+            .front()
+            .is_some_and(|&(t, _)| at.duration_since(t).is_ok_and(|age| age > self.window))
+        {
+            self.samples.pop_front();
+        }
+    }
+
+    // 短期电源 (UPS) 同样在放电，与电池一样继续采样
+    fn record_source(&mut self, source: PowerSourceType) {
+        let on_battery = matches!(source, PowerSourceType::Battery | PowerSourceType::ShortTerm);
+        if !on_battery {
+            self.samples.clear();
+        }
+        self.on_battery = Some(on_battery);
+    }
+
+    // 最小二乘拟合 电量(%) - 时间(秒) 的斜率，按当前电量外推到 0%
+    fn estimate(&self) -> Option<Duration> {
+        let &(start, _) = self.samples.front()?;
+        let &(_, current) = self.samples.back()?;
+        if self.samples.len() < 2 {
+            return None;
+        }
+
+        let points: Vec<(f64, f64)> = self
+            .samples
+            .iter()
+            .filter_map(|&(t, p)| Some((t.duration_since(start).ok()?.as_secs_f64(), p as f64)))
+            .collect();
+        let n = points.len() as f64;
+        let mean_t = points.iter().map(|&(t, _)| t).sum::<f64>() / n;
+        let mean_p = points.iter().map(|&(_, p)| p).sum::<f64>() / n;
+        let var_t: f64 = points.iter().map(|&(t, _)| (t - mean_t).powi(2)).sum();
+        if var_t <= 0.0 {
+            return None;
+        }
+        let cov: f64 = points.iter().map(|&(t, p)| (t - mean_t) * (p - mean_p)).sum();
+        let slope = cov / var_t;
+        time_to_empty(current as f64, slope)
+    }
+}
+
+// slope 为每秒变化的百分比；斜率极小时外推结果会超出 Duration 的范围，视为无法估算
+fn time_to_empty(current: f64, slope: f64) -> Option<Duration> {
+    if slope >= 0.0 {
+        return None;
+    }
+    Duration::try_from_secs_f64(current / -slope).ok()
+}
+
+pub struct BatteryEstimator {
+    state: Arc<Mutex<EstimatorState>>,
+    // 仅用于保持注册存活，Drop 时自动注销
+    _percent: PowerSettingObserver,
+    _source: AcDcObserver,
+}
+
+impl BatteryEstimator {
+    pub fn new() -> Result<Self, DeviceAwareError> {
+        Self::with_window(DEFAULT_WINDOW)
+    }
+
+    // window: 参与计算的样本时间范围；越短对负载变化越敏感，越长越平滑
+    pub fn with_window(window: Duration) -> Result<Self, DeviceAwareError> {
+        let state = Arc::new(Mutex::new(EstimatorState {
+            window,
+            samples: VecDeque::new(),
+            on_battery: None,
+        }));
+
+        let s = state.clone();
+        let source = AcDcObserver::new(move |source| {
+            s.lock().unwrap_or_else(|e| e.into_inner()).record_source(source);
+        })?;
+
+        let s = state.clone();
+        let percent = PowerSettingObserver::new_timestamped(GUID_BATTERY_PERCENTAGE_REMAINING, move |percent, at| {
+            s.lock().unwrap_or_else(|e| e.into_inner()).record_percent(at, percent);
+        })?;
+
+        Ok(BatteryEstimator {
+            state,
+            _percent: percent,
+            _source: source,
+        })
+    }
+
+    // 预计剩余续航；接通电源、样本不足或电量没有下降时返回 None
+    pub fn estimated_time_remaining(&self) -> Option<Duration> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).estimate()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::UNIX_EPOCH;
+
+    use super::*;
+
+    fn state() -> EstimatorState {
+        EstimatorState { window: Duration::MAX, samples: VecDeque::new(), on_battery: None }
+    }
+
+    #[test]
+    fn linear_discharge_extrapolates_to_empty() {
+        let mut state = state();
+        state.record_percent(UNIX_EPOCH, 50);
+        state.record_percent(UNIX_EPOCH + Duration::from_secs(60), 49);
+        assert_eq!(state.estimate(), Some(Duration::from_secs(49 * 60)));
+    }
+
+    // 外推结果超出 Duration 范围时返回 None 而不是 panic
+    #[test]
+    fn tiny_slope_returns_none() {
+        assert_eq!(time_to_empty(99.0, -1e-30), None);
+        assert_eq!(time_to_empty(99.0, 0.0), None);
+        assert_eq!(time_to_empty(60.0, -1.0), Some(Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn short_term_source_keeps_samples() {
+        let mut state = state();
+        state.record_percent(UNIX_EPOCH, 50);
+        state.record_source(PowerSourceType::ShortTerm);
+        state.record_percent(UNIX_EPOCH + Duration::from_secs(60), 49);
+        assert!(state.estimate().is_some());
+
+        state.record_source(PowerSourceType::AC);
+        assert_eq!(state.estimate(), None);
+    }
+}
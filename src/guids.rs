@@ -0,0 +1,54 @@
+use windows_core::GUID;
+
+// ============================================================================
+// 常用电源设置 GUID
+// 取值与 windows::Win32::System::SystemServices 中的定义一致；在这里单独声明，
+// 使调用方无需直接依赖 windows crate，mock 后端在非 Windows 平台上也能使用
+// ============================================================================
+
+pub const GUID_ACDC_POWER_SOURCE: GUID = GUID::from_u128(0x5d3e9a59_e9d5_4b00_a6bd_ff34ff516548);
+pub const GUID_POWER_SAVING_STATUS: GUID = GUID::from_u128(0xe00958c0_c213_4ace_ac77_fecced2eeea5);
+// Windows 11 的节能模式 (Energy Saver)；windows crate 0.58 尚未收录，取值见 winnt.h
+pub const GUID_ENERGY_SAVER_STATUS: GUID = GUID::from_u128(0x550e8400_e29b_41d4_a716_446655440000);
+pub const GUID_BATTERY_PERCENTAGE_REMAINING: GUID = GUID::from_u128(0xa7ad8041_b45a_4cae_87a3_eecbb468a9e1);
+pub const GUID_CONSOLE_DISPLAY_STATE: GUID = GUID::from_u128(0x6fe69556_704a_47a0_8f24_c28d936fda47);
+pub const GUID_MONITOR_POWER_ON: GUID = GUID::from_u128(0x02731015_4510_4526_99e6_e5a17ebd1aea);
+pub const GUID_LIDSWITCH_STATE_CHANGE: GUID = GUID::from_u128(0xba3e0f4d_b817_4094_a2d1_d56379e6a0f3);
+
+// 电池告警阈值 (百分比)，来自 Win32_System_SystemServices:
+// LEVEL_0 = 严重电量阈值 (powercfg 别名 BATLEVELCRIT)，LEVEL_1 = 低电量阈值 (BATLEVELLOW)
+pub const GUID_BATTERY_DISCHARGE_LEVEL_0: GUID = GUID::from_u128(0x9a66d8d7_4ff7_4ef9_b5a2_5a326ca2a469);
+pub const GUID_BATTERY_DISCHARGE_LEVEL_1: GUID = GUID::from_u128(0x8183ba9a_e910_48da_8769_14ae6dc1170a);
+
+// 处理器性能上/下限 (百分比)，即电源计划中的 "最大/最小处理器状态"；
+// 切换电源计划或在 AC/DC 之间切换导致取值变化时都会收到通知
+pub const GUID_PROCESSOR_THROTTLE_MAXIMUM: GUID = GUID::from_u128(0xbc5038f7_23e0_4960_96da_33abaf5935ec);
+pub const GUID_PROCESSOR_THROTTLE_MINIMUM: GUID = GUID::from_u128(0x893dee8e_2bef_41e0_89c6_b55d0929964c);
+
+// 当前电源计划的 "个性" (载荷为 16 字节 GUID，取值为下面三个之一)
+pub const GUID_POWERSCHEME_PERSONALITY: GUID = GUID::from_u128(0x245d8541_3943_4422_b025_13a784f679b7);
+// 不属于任何子组的电源设置 (例如 GUID_POWERSCHEME_PERSONALITY) 读写时使用的子组 GUID
+pub const NO_SUBGROUP_GUID: GUID = GUID::from_u128(0xfea3413e_7e05_4911_9a71_700331f1c294);
+// 高性能
+pub const GUID_MIN_POWER_SAVINGS: GUID = GUID::from_u128(0x8c5e7fda_e8bf_4a96_9a85_a6e23a8c635c);
+// 平衡
+pub const GUID_TYPICAL_POWER_SAVINGS: GUID = GUID::from_u128(0x381b4222_f694_41f0_9685_ff5bb260df2e);
+// 节能
+pub const GUID_MAX_POWER_SAVINGS: GUID = GUID::from_u128(0xa1841308_3541_4fab_bc81_f71556f20b4a);
+
+// 系统汇总的用户在场状态 (0 = 在场, 2 = 不活动)；只投递给服务等运行在 session 0 中的程序
+pub const GUID_GLOBAL_USER_PRESENCE: GUID = GUID::from_u128(0x786e8a1d_b427_4344_9207_09e70bdcbea9);
+// 当前会话的用户在场状态，取值同上；投递给本会话中的普通程序
+pub const GUID_SESSION_USER_PRESENCE: GUID = GUID::from_u128(0x3c0f4548_c03f_4c4d_b9f2_237ede686376);
+
+// 后台空闲任务触发器: 系统短期内不会进入空闲，适合执行后台维护任务。
+// 与其他 GUID 不同，它是一次性触发而不是设置值，载荷没有意义；系统也不会在时机结束时另行通知
+pub const GUID_IDLE_BACKGROUND_TASK: GUID = GUID::from_u128(0x515c31d8_f734_163d_a0fd_11a08c91e8f1);
+
+// ============================================================================
+// 常用设备接口类 GUID，供 DeviceNotifyObserver 使用
+// ============================================================================
+
+pub const GUID_DEVINTERFACE_USB_DEVICE: GUID = GUID::from_u128(0xa5dcbf10_6530_11d2_901f_00c04fb951ed);
+pub const GUID_DEVINTERFACE_MONITOR: GUID = GUID::from_u128(0xe6f07b5f_ee97_4a90_b076_33f57bf4eaa7);
+pub const GUID_DEVINTERFACE_HID: GUID = GUID::from_u128(0x4d1e55b2_f16f_11cf_88cb_001111000030);
@@ -0,0 +1,55 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+// 最近 N 次通知的环形缓冲；容量为 0 (默认) 时不记录，写满后丢弃最早的事件
+pub(crate) struct EventHistory<T = u32> {
+    // (容量, 事件)
+    inner: Mutex<(usize, VecDeque<(SystemTime, T)>)>,
+}
+
+impl<T> Default for EventHistory<T> {
+    fn default() -> Self {
+        EventHistory { inner: Mutex::new((0, VecDeque::new())) }
+    }
+}
+
+impl<T: Copy> EventHistory<T> {
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        EventHistory { inner: Mutex::new((capacity, VecDeque::new())) }
+    }
+
+    #[cfg(any(windows, feature = "mock"))]
+    pub(crate) fn set_capacity(&self, capacity: usize) {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner.0 = capacity;
+        while inner.1.len() > capacity {
+            inner.1.pop_front();
+        }
+    }
+
+    pub(crate) fn push(&self, at: SystemTime, val: T) {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let (capacity, events) = &mut *inner;
+        if *capacity == 0 {
+            return;
+        }
+        if events.len() == *capacity {
+            events.pop_front();
+        }
+        events.push_back((at, val));
+    }
+
+    // 按时间先后返回当前保留的事件
+    #[cfg(any(windows, feature = "mock"))]
+    pub(crate) fn snapshot(&self) -> Vec<(SystemTime, T)> {
+        let inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner.1.iter().copied().collect()
+    }
+
+    // 按时间先后返回晚于 since 的事件
+    pub(crate) fn since(&self, since: SystemTime) -> Vec<(SystemTime, T)> {
+        let inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner.1.iter().filter(|(at, _)| *at > since).copied().collect()
+    }
+}
@@ -0,0 +1,104 @@
+use crate::{EffectivePowerMode, MonitorPowerState, PowerSourceType};
+
+// ============================================================================
+// 本地化: 所有面向用户的文案都从 PowerLabels 获取，观察者本身不包含任何语言字符串
+// ============================================================================
+
+pub trait PowerLabels {
+    fn effective_mode(&self, mode: EffectivePowerMode) -> String;
+    fn power_source(&self, source: PowerSourceType) -> String;
+    fn saver_status(&self, is_on: bool) -> String;
+    fn battery_percent(&self, percent: u32) -> String;
+    fn monitor_power(&self, state: MonitorPowerState) -> String;
+}
+
+// 默认文案 (中文)
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ChineseLabels;
+
+impl PowerLabels for ChineseLabels {
+    fn effective_mode(&self, mode: EffectivePowerMode) -> String {
+        match mode {
+            EffectivePowerMode::BatterySaver => "滑块: 最左 (节电)".to_string(),
+            EffectivePowerMode::BetterBattery => "滑块: 较左 (更好电池)".to_string(),
+            EffectivePowerMode::Balanced => "滑块: 中间 (平衡)".to_string(),
+            EffectivePowerMode::BetterPerformance => "滑块: 较右 (最佳性能)".to_string(),
+            EffectivePowerMode::MaxPerformance => "滑块: 最右 (最大性能)".to_string(),
+            EffectivePowerMode::GameMode => "滑块: 游戏模式".to_string(),
+            EffectivePowerMode::Unknown(raw) => format!("滑块: 未知 ({})", raw),
+        }
+    }
+
+    fn power_source(&self, source: PowerSourceType) -> String {
+        match source {
+            PowerSourceType::AC => "电源: 电源供电".to_string(),
+            PowerSourceType::Battery => "电源: 电池供电".to_string(),
+            PowerSourceType::ShortTerm => "电源: 短期/UPS".to_string(),
+            PowerSourceType::Unknown => "电源: 未知".to_string(),
+        }
+    }
+
+    fn saver_status(&self, is_on: bool) -> String {
+        if is_on {
+            "节电模式: [已开启] (建议减少后台活动)".to_string()
+        } else {
+            "节电模式: [未开启]".to_string()
+        }
+    }
+
+    fn battery_percent(&self, percent: u32) -> String {
+        format!("电量: {}%", percent)
+    }
+
+    fn monitor_power(&self, state: MonitorPowerState) -> String {
+        match state {
+            MonitorPowerState::On => "显示器: 已点亮".to_string(),
+            MonitorPowerState::Off => "显示器: 已关闭 (休眠)".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct EnglishLabels;
+
+impl PowerLabels for EnglishLabels {
+    fn effective_mode(&self, mode: EffectivePowerMode) -> String {
+        match mode {
+            EffectivePowerMode::BatterySaver => "Slider: leftmost (battery saver)".to_string(),
+            EffectivePowerMode::BetterBattery => "Slider: left (better battery)".to_string(),
+            EffectivePowerMode::Balanced => "Slider: middle (balanced)".to_string(),
+            EffectivePowerMode::BetterPerformance => "Slider: right (better performance)".to_string(),
+            EffectivePowerMode::MaxPerformance => "Slider: rightmost (best performance)".to_string(),
+            EffectivePowerMode::GameMode => "Slider: game mode".to_string(),
+            EffectivePowerMode::Unknown(raw) => format!("Slider: unknown ({})", raw),
+        }
+    }
+
+    fn power_source(&self, source: PowerSourceType) -> String {
+        match source {
+            PowerSourceType::AC => "Power: AC adapter".to_string(),
+            PowerSourceType::Battery => "Power: battery".to_string(),
+            PowerSourceType::ShortTerm => "Power: short-term/UPS".to_string(),
+            PowerSourceType::Unknown => "Power: unknown".to_string(),
+        }
+    }
+
+    fn saver_status(&self, is_on: bool) -> String {
+        if is_on {
+            "Battery saver: [ON] (consider reducing background activity)".to_string()
+        } else {
+            "Battery saver: [OFF]".to_string()
+        }
+    }
+
+    fn battery_percent(&self, percent: u32) -> String {
+        format!("Battery: {}%", percent)
+    }
+
+    fn monitor_power(&self, state: MonitorPowerState) -> String {
+        match state {
+            MonitorPowerState::On => "Display: on".to_string(),
+            MonitorPowerState::Off => "Display: off (sleeping)".to_string(),
+        }
+    }
+}
@@ -0,0 +1,444 @@
+#[macro_use]
+mod logging;
+
+mod batch;
+#[cfg(any(windows, feature = "mock"))]
+mod change_signal;
+pub mod channel;
+// 回调上下文的所有权与平台无关，测试时在所有平台上编译
+#[cfg(any(all(windows, not(feature = "mock")), test))]
+mod context;
+// 与 power_observer 相同的后端切换
+#[cfg_attr(feature = "mock", path = "mock/device.rs")]
+#[cfg_attr(all(not(windows), not(feature = "mock")), path = "stub/device.rs")]
+pub mod device;
+mod dispatch;
+mod error;
+pub mod estimator;
+pub mod guids;
+mod history;
+pub mod labels;
+#[cfg(all(windows, not(feature = "mock")))]
+mod message_window;
+pub mod monitor;
+mod observer_ext;
+#[cfg(all(windows, not(feature = "mock")))]
+mod panic_guard;
+// 开启 `mock` feature 时换成不依赖 Win32 的实现，公开 API 保持一致；
+// 非 Windows 平台换成占位实现，构造函数统一返回 DeviceAwareError::Unsupported
+#[cfg_attr(feature = "mock", path = "mock/power_observer.rs")]
+#[cfg_attr(all(not(windows), not(feature = "mock")), path = "stub/power_observer.rs")]
+pub mod power_observer;
+// 回调栈与延迟释放同样与平台无关，测试时在所有平台上编译
+#[cfg(any(all(windows, not(feature = "mock")), test))]
+mod reentrancy;
+// 录制文件的编解码基于 serde_json
+#[cfg(feature = "serde")]
+pub mod replay;
+// 以服务状态句柄注册电源通知，仅真实 Win32 后端提供
+#[cfg(all(windows, not(feature = "mock")))]
+pub mod service;
+pub mod session;
+pub mod settings;
+#[cfg(any(windows, feature = "mock"))]
+mod slow_callback;
+pub mod status;
+#[cfg(feature = "tokio")]
+pub mod stream;
+#[cfg_attr(feature = "mock", path = "mock/suspend.rs")]
+#[cfg_attr(all(not(windows), not(feature = "mock")), path = "stub/suspend.rs")]
+pub mod suspend;
+pub mod thermal;
+pub mod typed;
+mod watchdog;
+
+pub use channel::{EffectiveModeChannel, PowerSettingChannel};
+pub use device::{DeviceEventCallback, DeviceNotifyObserver};
+pub use error::DeviceAwareError;
+pub use estimator::BatteryEstimator;
+pub use labels::{ChineseLabels, EnglishLabels, PowerLabels};
+pub use monitor::{
+    AdditionalSubscription, MultiSettingObserver, PowerEvent, PowerMonitor, PowerMonitorBuilder, SubscriptionId,
+    WaitTimeout,
+};
+pub use observer_ext::{decode_guid, decode_u32, probe_guids, MappedPowerSetting};
+pub use power_observer::{
+    EffectiveModeCallback, EffectiveModeObserver, PowerSettingCallback, PowerSettingObserver,
+    RawPowerSettingCallback,
+};
+#[cfg(feature = "serde")]
+pub use replay::{EventRecorder, ReplaySpeed};
+pub use session::SessionEvent;
+#[cfg(all(windows, not(feature = "mock")))]
+pub use session::{session_event_from_message, SessionObserver};
+pub use settings::{
+    BatteryAlarm, DisplayState, EnergySaverStatus, LidState, MonitorPowerState, PowerSchemePersonality,
+    ThrottlePercent, UserPresence,
+};
+pub use status::{
+    battery_state, current_power_scheme, current_power_state, system_power_status, BatteryState,
+    PowerState, SystemPowerStatus,
+};
+#[cfg(feature = "mock")]
+pub use status::{set_battery_state, set_system_power_status};
+pub use thermal::{thermal_state, CoolingMode, ThermalMonitor, ThermalState};
+#[cfg(feature = "mock")]
+pub use thermal::set_thermal_state;
+#[cfg(feature = "tokio")]
+pub use stream::{EffectiveModeStream, PowerSettingStream};
+pub use suspend::{SuspendResumeCallback, SuspendResumeObserver};
+pub use typed::{
+    AcDcObserver, AcDcObserverBuilder, BatteryAlarmObserver, BucketedBatteryObserver, OnBatteryObserver, PowerSaverObserver,
+    PowerSchemeObserver, SessionPresenceObserver, UserPresenceObserver,
+};
+
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(windows)]
+use windows::Win32::System::Power::EFFECTIVE_POWER_MODE;
+
+// ============================================================================
+// 辅助类型与描述
+// ============================================================================
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+#[repr(i32)]
+pub enum PowerSourceType {
+    // snake_case 会把 AC 变成 "a_c"，这里显式指定
+    #[cfg_attr(feature = "serde", serde(rename = "ac"))]
+    AC = 0,
+    Battery = 1,
+    ShortTerm = 2,
+    Unknown = -1,
+}
+
+impl From<u32> for PowerSourceType {
+    fn from(val: u32) -> Self {
+        match val {
+            0 => PowerSourceType::AC,
+            1 => PowerSourceType::Battery,
+            2 => PowerSourceType::ShortTerm,
+            _ => PowerSourceType::Unknown,
+        }
+    }
+}
+
+impl PowerSourceType {
+    // 稳定的整数表示，可用于跨进程传输
+    pub fn as_i32(self) -> i32 {
+        self as i32
+    }
+
+    #[inline]
+    pub fn is_ac(self) -> bool {
+        self == PowerSourceType::AC
+    }
+
+    #[inline]
+    pub fn is_battery(self) -> bool {
+        self == PowerSourceType::Battery
+    }
+
+    // 由外部供电: 电源适配器或短期电源 (UPS)
+    #[inline]
+    pub fn is_on_external(self) -> bool {
+        matches!(self, PowerSourceType::AC | PowerSourceType::ShortTerm)
+    }
+
+    // 机器可读的稳定标识，与 serde 名称一致；面向用户的文案见 Display / PowerLabels
+    pub fn key(&self) -> &'static str {
+        match self {
+            PowerSourceType::AC => "ac",
+            PowerSourceType::Battery => "battery",
+            PowerSourceType::ShortTerm => "short_term",
+            PowerSourceType::Unknown => "unknown",
+        }
+    }
+}
+
+impl TryFrom<i32> for PowerSourceType {
+    // 无法识别的整数原样返回
+    type Error = i32;
+
+    fn try_from(val: i32) -> Result<Self, Self::Error> {
+        match val {
+            0 => Ok(PowerSourceType::AC),
+            1 => Ok(PowerSourceType::Battery),
+            2 => Ok(PowerSourceType::ShortTerm),
+            -1 => Ok(PowerSourceType::Unknown),
+            other => Err(other),
+        }
+    }
+}
+
+// 电源模式滑块位置，Unknown 保留系统返回的原始值
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum EffectivePowerMode {
+    BatterySaver,
+    BetterBattery,
+    Balanced,
+    BetterPerformance,
+    MaxPerformance,
+    // EFFECTIVE_POWER_MODE 取值 5 (EffectivePowerModeGameMode)，与滑块位置无关: 只在开启了
+    // 游戏模式的系统上、游戏处于前台时由 V2 接口上报，结束后恢复为当时的滑块位置
+    GameMode,
+    Unknown(u8),
+}
+
+// 紧凑的单字节表示，与 EFFECTIVE_POWER_MODE 的取值一致: 0 = BatterySaver ... 5 = GameMode，
+// 其余值解码为 Unknown 并保留原值
+impl From<u8> for EffectivePowerMode {
+    fn from(val: u8) -> Self {
+        match val {
+            0 => EffectivePowerMode::BatterySaver,
+            1 => EffectivePowerMode::BetterBattery,
+            2 => EffectivePowerMode::Balanced,
+            3 => EffectivePowerMode::BetterPerformance,
+            4 => EffectivePowerMode::MaxPerformance,
+            5 => EffectivePowerMode::GameMode,
+            other => EffectivePowerMode::Unknown(other),
+        }
+    }
+}
+
+// From<u8> 的逆映射: Unknown(n) 编码为 n。系统上报的 Unknown 总是 6 及以上，
+// 因此两个方向互为往返；手工构造的 Unknown(0..=5) 会解码为对应的已知位置
+impl From<EffectivePowerMode> for u8 {
+    fn from(mode: EffectivePowerMode) -> Self {
+        match mode {
+            EffectivePowerMode::BatterySaver => 0,
+            EffectivePowerMode::BetterBattery => 1,
+            EffectivePowerMode::Balanced => 2,
+            EffectivePowerMode::BetterPerformance => 3,
+            EffectivePowerMode::MaxPerformance => 4,
+            EffectivePowerMode::GameMode => 5,
+            EffectivePowerMode::Unknown(raw) => raw,
+        }
+    }
+}
+
+#[cfg(windows)]
+impl From<EFFECTIVE_POWER_MODE> for EffectivePowerMode {
+    fn from(mode: EFFECTIVE_POWER_MODE) -> Self {
+        // 新系统可能增加滑块位置，保留原始值；超出 u8 范围的异常值饱和为 u8::MAX
+        u8::try_from(mode.0).map_or(EffectivePowerMode::Unknown(u8::MAX), EffectivePowerMode::from)
+    }
+}
+
+// Display 输出与 describe_* 一致，使用默认的中文文案
+impl fmt::Display for PowerSourceType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&ChineseLabels.power_source(*self))
+    }
+}
+
+impl fmt::Display for EffectivePowerMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&ChineseLabels.effective_mode(*self))
+    }
+}
+
+impl EffectivePowerMode {
+    // 单字节数值表示，见 From<EffectivePowerMode> for u8
+    pub fn as_u8(self) -> u8 {
+        u8::from(self)
+    }
+
+    // 游戏正在前台运行，例如可据此暂停非紧急的通知
+    #[inline]
+    pub fn is_game_mode(self) -> bool {
+        self == EffectivePowerMode::GameMode
+    }
+
+    // 机器可读的稳定标识，与 serde 名称一致；Unknown 不区分原始值，需要时用 as_u8
+    pub fn key(&self) -> &'static str {
+        match self {
+            EffectivePowerMode::BatterySaver => "battery_saver",
+            EffectivePowerMode::BetterBattery => "better_battery",
+            EffectivePowerMode::Balanced => "balanced",
+            EffectivePowerMode::BetterPerformance => "better_performance",
+            EffectivePowerMode::MaxPerformance => "max_performance",
+            EffectivePowerMode::GameMode => "game_mode",
+            EffectivePowerMode::Unknown(_) => "unknown",
+        }
+    }
+
+    // 滑块从节电到性能的相对位置；游戏模式视为比最大性能更偏向性能，Unknown 无法比较
+    fn performance_rank(self) -> Option<u8> {
+        match self {
+            EffectivePowerMode::BatterySaver => Some(0),
+            EffectivePowerMode::BetterBattery => Some(1),
+            EffectivePowerMode::Balanced => Some(2),
+            EffectivePowerMode::BetterPerformance => Some(3),
+            EffectivePowerMode::MaxPerformance => Some(4),
+            EffectivePowerMode::GameMode => Some(5),
+            EffectivePowerMode::Unknown(_) => None,
+        }
+    }
+}
+
+// 一次滑块位置变化，由 EffectiveModeObserver::new_with_change 投递
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EffectiveModeChange {
+    pub from: EffectivePowerMode,
+    pub to: EffectivePowerMode,
+}
+
+impl EffectiveModeChange {
+    // Some(true) = 向性能方向移动，Some(false) = 向节电方向移动；
+    // 任一端为 Unknown 或位置相同时返回 None
+    pub fn moved_toward_performance(&self) -> Option<bool> {
+        let from = self.from.performance_rank()?;
+        let to = self.to.performance_rank()?;
+        (from != to).then_some(to > from)
+    }
+}
+
+// 从字符串解析: 接受变体名 (不区分大小写，可带下划线，如 "BatterySaver" / "battery_saver")、
+// 数值索引 ("0".."5"，其他数值解析为 Unknown) 以及 Display 输出的文案
+impl FromStr for EffectivePowerMode {
+    type Err = ParseEffectivePowerModeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let input = s.trim();
+        if let Ok(index) = input.parse::<u8>() {
+            return Ok(EffectivePowerMode::from(index));
+        }
+
+        let name = input.replace('_', "").to_ascii_lowercase();
+        let mode = match name.as_str() {
+            "batterysaver" => EffectivePowerMode::BatterySaver,
+            "betterbattery" => EffectivePowerMode::BetterBattery,
+            "balanced" => EffectivePowerMode::Balanced,
+            "betterperformance" => EffectivePowerMode::BetterPerformance,
+            "maxperformance" => EffectivePowerMode::MaxPerformance,
+            "gamemode" => EffectivePowerMode::GameMode,
+            _ => {
+                return KNOWN_EFFECTIVE_MODES
+                    .into_iter()
+                    .find(|mode| mode.to_string() == input)
+                    .ok_or_else(|| ParseEffectivePowerModeError { input: input.to_string() });
+            }
+        };
+        Ok(mode)
+    }
+}
+
+const KNOWN_EFFECTIVE_MODES: [EffectivePowerMode; 6] = [
+    EffectivePowerMode::BatterySaver,
+    EffectivePowerMode::BetterBattery,
+    EffectivePowerMode::Balanced,
+    EffectivePowerMode::BetterPerformance,
+    EffectivePowerMode::MaxPerformance,
+    EffectivePowerMode::GameMode,
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseEffectivePowerModeError {
+    input: String,
+}
+
+impl fmt::Display for ParseEffectivePowerModeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown effective power mode {:?}, expected a variant name such as \"Balanced\" or an index 0-255",
+            self.input
+        )
+    }
+}
+
+impl std::error::Error for ParseEffectivePowerModeError {}
+
+// 睡眠 / 唤醒事件
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum SystemPowerEvent {
+    Suspend,
+    Resume,
+}
+
+// 设备接口到达 / 移除事件，携带设备接口路径 (SymbolicLink)
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum DeviceEvent {
+    Arrival(String),
+    Removal(String),
+}
+
+// 以下 describe_* 使用默认的中文文案，需要其他语言时请直接使用 PowerLabels
+pub fn describe_effective_mode(mode: EffectivePowerMode) -> String {
+    ChineseLabels.effective_mode(mode)
+}
+
+pub fn describe_power_source(source: PowerSourceType) -> String {
+    ChineseLabels.power_source(source)
+}
+
+pub fn describe_saver_status(is_on: bool) -> String {
+    ChineseLabels.saver_status(is_on)
+}
+
+// 节电模式没有单独的枚举，这里提供与 PowerSourceType::key 对应的稳定标识
+pub fn saver_status_key(is_on: bool) -> &'static str {
+    if is_on { "on" } else { "off" }
+}
+
+pub fn describe_battery_percent(percent: u32) -> String {
+    ChineseLabels.battery_percent(percent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0..=5 解码为具名变体，6 及以上保留原值；两个方向互为往返
+    #[test]
+    fn effective_mode_round_trips_raw_values() {
+        for raw in 0..=10u8 {
+            let mode = EffectivePowerMode::from(raw);
+            if raw <= 5 {
+                assert_eq!(mode, KNOWN_EFFECTIVE_MODES[raw as usize]);
+            } else {
+                assert_eq!(mode, EffectivePowerMode::Unknown(raw));
+            }
+            assert_eq!(mode.as_u8(), raw);
+        }
+    }
+
+    // 每个变体: 整数表示往返，以及 is_ac / is_battery / is_on_external 的取值
+    #[test]
+    fn power_source_predicates_per_variant() {
+        let cases = [
+            (PowerSourceType::AC, true, false, true),
+            (PowerSourceType::Battery, false, true, false),
+            (PowerSourceType::ShortTerm, false, false, true),
+            (PowerSourceType::Unknown, false, false, false),
+        ];
+        for (source, ac, battery, external) in cases {
+            assert_eq!(PowerSourceType::from(source.as_i32() as u32), source);
+            assert_eq!(PowerSourceType::try_from(source.as_i32()), Ok(source));
+            assert_eq!(source.is_ac(), ac, "{:?}", source);
+            assert_eq!(source.is_battery(), battery, "{:?}", source);
+            assert_eq!(source.is_on_external(), external, "{:?}", source);
+        }
+    }
+
+    // 取值 5 是游戏模式，与滑块位置区分开
+    #[test]
+    fn value_five_decodes_to_game_mode() {
+        let mode = EffectivePowerMode::from(5u8);
+        assert_eq!(mode, EffectivePowerMode::GameMode);
+        assert!(mode.is_game_mode());
+        assert_eq!("5".parse::<EffectivePowerMode>(), Ok(EffectivePowerMode::GameMode));
+        assert!(!EffectivePowerMode::MaxPerformance.is_game_mode());
+    }
+}
@@ -0,0 +1,43 @@
+// ============================================================================
+// 日志: 开启 `logging` feature 时转发到 log crate，否则编译为空操作
+// ============================================================================
+
+// 关闭 feature 时仍然对参数做一次 format_args!，避免出现未使用变量的警告
+// mock 后端只用到其中一部分宏
+#![allow(unused_macros)]
+
+macro_rules! log_error {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "logging")]
+        ::log::error!($($arg)*);
+        #[cfg(not(feature = "logging"))]
+        let _ = format_args!($($arg)*);
+    }};
+}
+
+macro_rules! log_warn {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "logging")]
+        ::log::warn!($($arg)*);
+        #[cfg(not(feature = "logging"))]
+        let _ = format_args!($($arg)*);
+    }};
+}
+
+macro_rules! log_debug {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "logging")]
+        ::log::debug!($($arg)*);
+        #[cfg(not(feature = "logging"))]
+        let _ = format_args!($($arg)*);
+    }};
+}
+
+macro_rules! log_trace {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "logging")]
+        ::log::trace!($($arg)*);
+        #[cfg(not(feature = "logging"))]
+        let _ = format_args!($($arg)*);
+    }};
+}
@@ -1,275 +1,364 @@
-use std::ffi::c_void;
-use std::sync::{Arc, Mutex};
+use std::ffi::OsString;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::thread;
-use std::time::Duration;
-
-use windows::core::{GUID};
-use windows::Win32::Foundation::HANDLE;
-use windows::Win32::System::Power::{
-    PowerRegisterForEffectivePowerModeNotifications, PowerUnregisterFromEffectivePowerModeNotifications,
-    RegisterPowerSettingNotification, UnregisterPowerSettingNotification,
-    EFFECTIVE_POWER_MODE, EFFECTIVE_POWER_MODE_V2,
-    HPOWERNOTIFY, DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS,
-    POWERBROADCAST_SETTING,
-};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use windows::Win32::UI::WindowsAndMessaging::{
-    DEVICE_NOTIFY_CALLBACK, PBT_POWERSETTINGCHANGE, };
+use clap::Parser;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use serde::Serialize;
 
-use windows::Win32::System::SystemServices::{
-    GUID_POWER_SAVING_STATUS, GUID_ACDC_POWER_SOURCE,
+use device_aware::{
+    current_power_state, replay, ChineseLabels, EnglishLabels, EventRecorder, MonitorPowerState, PowerEvent,
+    PowerLabels, PowerMonitorBuilder, PowerSettingObserver, ReplaySpeed,
 };
 
 // ============================================================================
-// 辅助类型与描述
+// 演示用的极简日志输出: info 直接打印消息，其余级别带上级别前缀
+// 通过 RUST_LOG=debug / trace 查看库内部的注册、回调与注销日志
 // ============================================================================
 
-#[derive(Debug, PartialEq, Clone, Copy)]
-enum PowerSourceType {
-    AC = 0,
-    Battery = 1,
-    ShortTerm = 2,
-    Unknown = -1,
-}
+struct StdoutLogger;
 
-impl From<u32> for PowerSourceType {
-    fn from(val: u32) -> Self {
-        match val {
-            0 => PowerSourceType::AC,
-            1 => PowerSourceType::Battery,
-            2 => PowerSourceType::ShortTerm,
-            _ => PowerSourceType::Unknown,
+impl Log for StdoutLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        // println! 内部会锁定 stdout，多个回调线程并发输出也不会交错
+        if record.level() == Level::Info {
+            println!("{}", record.args());
+        } else {
+            println!("[{}] {}", record.level(), record.args());
         }
     }
+
+    fn flush(&self) {}
 }
 
-fn describe_effective_mode(mode: EFFECTIVE_POWER_MODE) -> String {
-    match mode.0 {
-        0 => "滑块: 最左 (节电)".to_string(),
-        1 => "滑块: 较左 (更好电池)".to_string(),
-        2 => "滑块: 中间 (平衡)".to_string(),
-        3 => "滑块: 较右 (最佳性能)".to_string(),
-        4 => "滑块: 最右 (最大性能)".to_string(),
-        5 => "滑块: 游戏模式".to_string(),
-        _ => "滑块: 未知".to_string(),
-    }
+static LOGGER: StdoutLogger = StdoutLogger;
+
+fn init_logger() {
+    let level = std::env::var("RUST_LOG")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(LevelFilter::Info);
+    let _ = log::set_logger(&LOGGER);
+    log::set_max_level(level);
 }
 
-fn describe_power_source(source: PowerSourceType) -> String {
-    match source {
-        PowerSourceType::AC => "电源: 电源供电".to_string(),
-        PowerSourceType::Battery => "电源: 电池供电".to_string(),
-        PowerSourceType::ShortTerm => "电源: 短期/UPS".to_string(),
-        PowerSourceType::Unknown => "电源: 未知".to_string(),
+// ============================================================================
+// Ctrl+C: 通知主循环退出，让观察者正常 Drop 并注销系统通知
+// ============================================================================
+
+static RUNNING: AtomicBool = AtomicBool::new(true);
+
+#[cfg(windows)]
+unsafe extern "system" fn ctrl_handler(ctrl_type: u32) -> windows::Win32::Foundation::BOOL {
+    use windows::Win32::System::Console::{CTRL_BREAK_EVENT, CTRL_C_EVENT};
+
+    match ctrl_type {
+        CTRL_C_EVENT | CTRL_BREAK_EVENT => {
+            RUNNING.store(false, Ordering::SeqCst);
+            true.into()
+        }
+        _ => false.into(),
     }
 }
 
-fn describe_saver_status(is_on: bool) -> String {
-    if is_on {
-        "节电模式: [已开启] (建议减少后台活动)".to_string()
-    } else {
-        "节电模式: [未开启]".to_string()
-    }
+#[cfg(windows)]
+fn install_ctrl_handler() -> windows_core::Result<()> {
+    unsafe { windows::Win32::System::Console::SetConsoleCtrlHandler(Some(ctrl_handler), true) }
 }
 
 // ============================================================================
-// 1. EffectiveModeObserver (修复版)
+// 命令行参数: 未选择任何事件类别时订阅全部
 // ============================================================================
 
-// 定义回调类型别名，方便处理
-type EffectiveModeCallback = Box<dyn Fn(EFFECTIVE_POWER_MODE) + Send + Sync>;
-
-struct EffectiveModeObserver {
-    handle: *mut c_void,
-    // 我们保存原始指针，以便在 Drop 时将其转回 Box 进行释放
-    raw_context: *mut EffectiveModeCallback, 
+const CATEGORIES: &str = "事件类别 (可组合，均未指定时订阅全部)";
+const OUTPUT: &str = "输出";
+const REPLAY: &str = "录制与回放";
+
+#[derive(Debug, Parser)]
+#[command(name = "DeviceAware", about = "监听 Windows 电源事件并打印到终端")]
+struct Options {
+    #[arg(long = "mode", help = "电源模式滑块", help_heading = CATEGORIES)]
+    effective_mode: bool,
+    #[arg(long = "source", help = "AC/DC 电源类型", help_heading = CATEGORIES)]
+    power_source: bool,
+    #[arg(long = "saver", help = "节电模式", help_heading = CATEGORIES)]
+    saver_status: bool,
+    #[arg(long = "battery", help = "剩余电量", help_heading = CATEGORIES)]
+    battery_percent: bool,
+    #[arg(long = "monitor", help = "显示器开/关", help_heading = CATEGORIES)]
+    monitor_power: bool,
+
+    #[arg(long, help = "打印一次当前状态后退出", help_heading = OUTPUT)]
+    once: bool,
+    #[arg(long, visible_alias = "ndjson", help = "每个事件输出一行 JSON", help_heading = OUTPUT)]
+    json: bool,
+    #[arg(long, help = "使用英文文案", help_heading = OUTPUT)]
+    english: bool,
+
+    #[arg(
+        long,
+        value_name = "文件",
+        help = "同时把事件追加写入文件 (NDJSON，格式同 --json)",
+        help_heading = REPLAY
+    )]
+    record: Option<String>,
+    #[arg(
+        long,
+        value_name = "文件",
+        help = "不订阅系统通知，按录制时的间隔回放文件中的事件后退出",
+        help_heading = REPLAY
+    )]
+    replay: Option<String>,
+    #[arg(
+        long,
+        value_name = "倍数",
+        allow_negative_numbers = true,
+        help = "回放倍速，默认 1；0 表示不等待",
+        help_heading = REPLAY
+    )]
+    speed: Option<f64>,
 }
 
-impl EffectiveModeObserver {
-    pub fn new<F>(handler: F) -> Self 
-    where F: Fn(EFFECTIVE_POWER_MODE) + Send + Sync + 'static 
+impl Options {
+    // 解析后补全默认值: 未选择任何事件类别时订阅全部
+    fn parse_from_args<I, T>(args: I) -> Result<Self, clap::Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
     {
-        // 1. 创建闭包的胖指针 Box<dyn Fn>
-        let callback: EffectiveModeCallback = Box::new(handler);
-        
-        // 2. 将胖指针装入外层 Box，并转换为原始指针 (Double Boxing)
-        // 这样 raw_context 就是一个指向 "Box<dyn Fn>" 的瘦指针 (8 bytes)，适合传给 void*
-        let raw_context = Box::into_raw(Box::new(callback));
-        
-        let mut handle = std::ptr::null_mut();
-
-        unsafe {
-            let hr = PowerRegisterForEffectivePowerModeNotifications(
-                EFFECTIVE_POWER_MODE_V2,
-                Some(Self::static_cb),
-                Some(raw_context as *const c_void), // 传入瘦指针
-                &mut handle,
-            );
-
-            if hr.is_err() {
-                eprintln!("PowerRegisterForEffectivePowerModeNotifications failed");
-                // 如果注册失败，我们需要手动回收内存，否则泄漏
-                let _ = Box::from_raw(raw_context); 
-            }
-        }
-
-        EffectiveModeObserver {
-            handle,
-            raw_context,
+        let mut options = Options::try_parse_from(args)?;
+        let any_selected = options.effective_mode
+            || options.power_source
+            || options.saver_status
+            || options.battery_percent
+            || options.monitor_power;
+        if !any_selected {
+            options.effective_mode = true;
+            options.power_source = true;
+            options.saver_status = true;
+            options.battery_percent = true;
+            options.monitor_power = true;
         }
+        Ok(options)
     }
+}
 
-    unsafe extern "system" fn static_cb(mode: EFFECTIVE_POWER_MODE, context: *const c_void) {
-        if !context.is_null() {
-            // 3. 将 void* 转回为指向 EffectiveModeCallback 的指针
-            let cb_ptr: *const Box<dyn Fn(EFFECTIVE_POWER_MODE) + Send + Sync> = context as *const EffectiveModeCallback;
-            // 4. 解引用得到 &Box<dyn Fn>，再调用
-            unsafe {
-                (*cb_ptr)(mode);
-            }
-        }
-    }
+// ============================================================================
+// --json: 每个事件输出一行 JSON，便于通过管道交给 jq 等工具处理
+// PowerEvent 行由库的录制格式编码 (replay::event_to_json)，显示器行用同样的 ts / kind / value 结构
+// ============================================================================
+
+#[derive(Serialize)]
+struct MonitorPowerLine {
+    ts: u64,
+    kind: &'static str,
+    value: MonitorPowerState,
 }
 
-impl Drop for EffectiveModeObserver {
-    fn drop(&mut self) {
-        if !self.handle.is_null() {
-            unsafe {
-                let _ = PowerUnregisterFromEffectivePowerModeNotifications(self.handle);
-                // 5. 关键：手动回收内存。将 raw pointer 转回 Box，离开作用域自动释放。
-                let _ = Box::from_raw(self.raw_context);
-            }
-        }
-    }
+fn event_json(event: PowerEvent) -> String {
+    replay::event_to_json(SystemTime::now(), event)
+}
+
+fn monitor_power_json(state: MonitorPowerState) -> String {
+    let ts = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis() as u64);
+    let line = MonitorPowerLine { ts, kind: "monitor_power", value: state };
+    serde_json::to_string(&line).expect("monitor power line is always serializable")
 }
 
 // ============================================================================
-// 2. PowerSettingObserver (修复版)
+// 业务入口 (main)
 // ============================================================================
 
-type PowerSettingCallback = Box<dyn Fn(u32) + Send + Sync>;
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // --help / --version 以及错误参数由 clap 打印并以相应的退出码结束
+    let options = Options::parse_from_args(std::env::args_os()).unwrap_or_else(|e| e.exit());
 
-struct PowerSettingObserver {
-    handle: Option<HPOWERNOTIFY>, 
-    raw_context: *mut PowerSettingCallback,
-}
+    init_logger();
+    #[cfg(windows)]
+    install_ctrl_handler()?;
 
-impl PowerSettingObserver {
-    pub fn new<F>(guid: GUID, handler: F) -> Self
-    where F: Fn(u32) + Send + Sync + 'static
-    {
-        // 1. Double Boxing 策略
-        let callback: PowerSettingCallback = Box::new(handler);
-        let raw_context = Box::into_raw(Box::new(callback));
-
-        // 2. 这里的 Context 必须是指向我们堆内存的指针
-        let mut params = DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS {
-            Callback: Some(Self::static_callback),
-            Context: raw_context as *mut c_void, 
-        };
+    let json = options.json;
+    let labels: Arc<dyn PowerLabels + Send + Sync> = if options.english {
+        Arc::new(EnglishLabels)
+    } else {
+        Arc::new(ChineseLabels)
+    };
+
+    if options.once {
+        return print_current_state(json, labels.as_ref());
+    }
 
-        let result = unsafe {
-            RegisterPowerSettingNotification(
-                HANDLE(&mut params as *mut _ as *mut c_void),
-                &guid,
-                DEVICE_NOTIFY_CALLBACK, 
-            )
+    if let Some(path) = &options.replay {
+        let speed = match options.speed {
+            None => ReplaySpeed::RealTime,
+            Some(speed) => ReplaySpeed::Accelerated(speed),
         };
-        
-        let handle = match result {
-            Ok(h) => Some(h),
-            Err(e) => {
-                eprintln!("RegisterPowerSettingNotification failed for GUID {:?}: {:?}", guid, e);
-                unsafe { let _ = Box::from_raw(raw_context); } // 失败回滚
-                None
+        let count = replay::replay(path, speed, |event| {
+            if json {
+                println!("{}", event_json(event));
+            } else {
+                log::info!("{}", describe(labels.as_ref(), event));
             }
-        };
+        })?;
+        eprintln!("回放完成，共 {} 个事件", count);
+        return Ok(());
+    }
 
-        PowerSettingObserver {
-            handle,
-            raw_context,
-        }
+    let recorder = options.record.as_deref().map(EventRecorder::create).transpose()?;
+
+    // json 模式下 stdout 只输出 JSON 行，提示信息改写到 stderr
+    if json {
+        eprintln!("启动电源监控，事件以 NDJSON 格式输出到 stdout...");
+    } else {
+        log::info!("启动电源监控...");
+        log::info!("--------------------------------------------------");
     }
 
-    unsafe extern "system" fn static_callback(
-        context: *const c_void,
-        type_: u32,
-        setting: *const c_void,
-    ) -> u32 {
-        if type_ == PBT_POWERSETTINGCHANGE && !context.is_null() && !setting.is_null() {
-            let p_setting = unsafe { &*(setting as *const POWERBROADCAST_SETTING) };
-            
-            if p_setting.DataLength == std::mem::size_of::<u32>() as u32 {
-                // ---------------- 修复开始 ----------------
-                
-                // 1. 获取 Data 字段的首地址指针
-                let data_ptr = p_setting.Data.as_ptr();
-
-                // 2. 根据 DataLength (4) 手动构建切片，绕过 [u8; 1] 的静态限制
-                let data_slice = unsafe { std::slice::from_raw_parts(data_ptr, p_setting.DataLength as usize) };
-                
-                // 3. 安全转换 (这里就不需要 try_into 导致的 panic 风险了)
-                let val = u32::from_ne_bytes(data_slice.try_into().unwrap_or([0, 0, 0, 0]));
-                
-                // ---------------- 修复结束 ----------------
-                
-                // 3. 恢复指针并调用
-                let cb_ptr: *const Box<dyn Fn(u32) + Send + Sync> = context as *const PowerSettingCallback;
-                unsafe {
-                    (*cb_ptr)(val);
-                }
-            }
+    let mut builder = PowerMonitorBuilder::new();
+    if options.effective_mode {
+        builder = builder.with_effective_mode();
+    }
+    if options.power_source {
+        builder = builder.with_power_source();
+    }
+    if options.saver_status {
+        builder = builder.with_saver_status();
+    }
+    if options.battery_percent {
+        builder = builder.with_battery_percent();
+    }
+
+    let l1 = labels.clone();
+    let monitor = builder.build(move |event| {
+        if let Some(recorder) = &recorder {
+            recorder.record(event);
+        }
+        if json {
+            println!("{}", event_json(event));
+            return;
         }
-        0 
+        log::info!("{}", describe(l1.as_ref(), event));
+    })?;
+
+    let monitor_obs = if options.monitor_power {
+        let l2 = labels.clone();
+        Some(PowerSettingObserver::new_monitor_power(move |state| {
+            if json {
+                println!("{}", monitor_power_json(state));
+            } else {
+                log::info!("{}", l2.monitor_power(state));
+            }
+        })?)
+    } else {
+        None
+    };
+
+    while RUNNING.load(Ordering::SeqCst) {
+        thread::sleep(Duration::from_millis(200));
+    }
+
+    if json {
+        eprintln!("正在退出，注销电源通知...");
+    } else {
+        log::info!("正在退出，注销电源通知...");
     }
+    drop(monitor_obs);
+    drop(monitor);
+    Ok(())
 }
 
-impl Drop for PowerSettingObserver {
-    fn drop(&mut self) {
-        if let Some(h) = self.handle {
-            unsafe {
-                let _ = UnregisterPowerSettingNotification(h);
-                // 4. 回收内存
-                let _ = Box::from_raw(self.raw_context);
-            }
+fn describe(labels: &dyn PowerLabels, event: PowerEvent) -> String {
+    match event {
+        PowerEvent::EffectiveMode(mode) => labels.effective_mode(mode),
+        PowerEvent::PowerSource(source) => labels.power_source(source),
+        PowerEvent::SaverStatus(is_on) => labels.saver_status(is_on),
+        PowerEvent::BatteryPercent(percent) => labels.battery_percent(percent),
+    }
+}
+
+// --once: 打印一次当前状态后退出
+fn print_current_state(json: bool, labels: &dyn PowerLabels) -> Result<(), Box<dyn std::error::Error>> {
+    let state = current_power_state()?;
+    let mut events = vec![PowerEvent::PowerSource(state.source), PowerEvent::SaverStatus(state.saver_on)];
+    if let Some(mode) = state.effective_mode {
+        events.push(PowerEvent::EffectiveMode(mode));
+    }
+    if let Some(percent) = state.battery_percent {
+        events.push(PowerEvent::BatteryPercent(percent as u32));
+    }
+
+    for event in events {
+        if json {
+            println!("{}", event_json(event));
+        } else {
+            println!("{}", describe(labels, event));
         }
     }
+    Ok(())
 }
 
-// ============================================================================
-// 业务入口 (main)
-// ============================================================================
+#[cfg(test)]
+mod tests {
+    use clap::error::ErrorKind;
+    use clap::CommandFactory;
 
-fn main() {
-    let io_mutex = Arc::new(Mutex::new(()));
+    use super::*;
 
-    // 使用 Arc 克隆引用，因为闭包需要 'static 生命周期
-    let safe_print = move |msg: String| {
-        let _lock = io_mutex.lock().unwrap();
-        println!("{}", msg);
-    };
+    fn parse(args: &[&str]) -> Result<Options, clap::Error> {
+        Options::parse_from_args(std::iter::once("DeviceAware").chain(args.iter().copied()))
+    }
 
-    println!("启动全维度电源监控 (AC/DC + 滑块 + 节电模式)...");
-    println!("--------------------------------------------------");
+    #[test]
+    fn command_definition_is_valid() {
+        Options::command().debug_assert();
+    }
 
-    let sp1 = safe_print.clone();
-    let _perf_obs = EffectiveModeObserver::new(move |mode| {
-        sp1(describe_effective_mode(mode));
-    });
+    #[test]
+    fn no_category_selects_all() {
+        let options = parse(&["--json"]).unwrap();
+        assert!(options.json);
+        assert!(options.effective_mode && options.power_source && options.saver_status);
+        assert!(options.battery_percent && options.monitor_power);
+    }
 
-    let sp2 = safe_print.clone();
-    let _source_obs = PowerSettingObserver::new(GUID_ACDC_POWER_SOURCE, move |val| {
-        let source = PowerSourceType::from(val);
-        sp2(describe_power_source(source));
-    });
+    #[test]
+    fn explicit_categories_are_kept() {
+        let options = parse(&["--mode", "--battery", "--ndjson", "--speed", "2.5"]).unwrap();
+        assert!(options.effective_mode && options.battery_percent && options.json);
+        assert!(!options.power_source && !options.saver_status && !options.monitor_power);
+        assert_eq!(options.speed, Some(2.5));
+        // 非正数倍速按不等待回放，负数不应被当成选项
+        assert_eq!(parse(&["--speed", "-1"]).unwrap().speed, Some(-1.0));
+    }
 
-    let sp3 = safe_print.clone();
-    let _saver_obs = PowerSettingObserver::new(GUID_POWER_SAVING_STATUS, move |val| {
-        let is_on = val != 0;
-        sp3(describe_saver_status(is_on));
-    });
+    #[test]
+    fn help_is_generated() {
+        let error = parse(&["--help"]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::DisplayHelp);
+        let help = error.to_string();
+        assert!(help.contains("--replay <文件>"));
+        assert!(help.contains(CATEGORIES));
+    }
+
+    #[test]
+    fn unknown_argument_is_rejected() {
+        assert_eq!(parse(&["--verbose"]).unwrap_err().kind(), ErrorKind::UnknownArgument);
+        assert_eq!(parse(&["mode"]).unwrap_err().kind(), ErrorKind::UnknownArgument);
+    }
 
-    loop {
-        thread::sleep(Duration::from_secs(1));
+    #[test]
+    fn malformed_values_are_rejected() {
+        assert_eq!(parse(&["--speed", "fast"]).unwrap_err().kind(), ErrorKind::ValueValidation);
+        assert_eq!(parse(&["--record"]).unwrap_err().kind(), ErrorKind::InvalidValue);
+        assert_eq!(parse(&["--speed"]).unwrap_err().kind(), ErrorKind::InvalidValue);
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,187 @@
+use std::cell::RefCell;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use windows::core::{w, Error, PCWSTR};
+use windows::Win32::Foundation::{GetLastError, ERROR_CLASS_ALREADY_EXISTS, HWND, LPARAM, LRESULT, WPARAM};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DefWindowProcW, DestroyWindow, DispatchMessageW, GetMessageW, PostMessageW,
+    PostQuitMessage, RegisterClassW, TranslateMessage, HWND_MESSAGE, MSG, WINDOW_EX_STYLE, WINDOW_STYLE,
+    WM_CLOSE, WM_DESTROY, WM_DEVICECHANGE, WM_POWERBROADCAST, WNDCLASSW,
+};
+
+use crate::panic_guard::guard_callback;
+use crate::DeviceAwareError;
+
+// ============================================================================
+// MessageWindow: 需要窗口句柄的注册 (DEVICE_NOTIFY_WINDOW_HANDLE、WTSRegisterSessionNotification 等)
+// 共用的隐藏窗口。窗口是 HWND_MESSAGE 下的纯消息窗口，在专用线程上创建并运行消息循环，
+// 收到的消息按消息号分发给 on_message 注册的回调。
+// 纯消息窗口收不到广播消息，只能收到显式注册到该句柄的通知
+// ============================================================================
+
+const CLASS_NAME: PCWSTR = w!("DeviceAwareMessageWindow");
+
+pub(crate) type MessageHandler = Arc<dyn Fn(WPARAM, LPARAM) + Send + Sync>;
+
+type Routes = Mutex<Vec<(u32, MessageHandler)>>;
+
+thread_local! {
+    // 窗口过程只在创建窗口的线程上运行，因此路由表放在该线程的线程局部变量中
+    static ROUTES: RefCell<Option<Arc<Routes>>> = const { RefCell::new(None) };
+}
+
+pub(crate) struct MessageWindow {
+    // HWND 不是 Send，这里保存句柄值；窗口只在消息线程上销毁
+    hwnd: isize,
+    routes: Arc<Routes>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl MessageWindow {
+    // 启动消息线程并等待窗口创建完成
+    pub(crate) fn new() -> Result<Self, DeviceAwareError> {
+        let routes: Arc<Routes> = Arc::default();
+        let thread_routes = routes.clone();
+        let (tx, rx) = mpsc::channel();
+        let thread = thread::Builder::new()
+            .name("device-aware-message-window".to_string())
+            .spawn(move || {
+                ROUTES.with(|r| *r.borrow_mut() = Some(thread_routes));
+                let hwnd = match unsafe { create_window() } {
+                    Ok(hwnd) => hwnd,
+                    Err(error) => {
+                        let _ = tx.send(Err(error));
+                        return;
+                    }
+                };
+                let _ = tx.send(Ok(hwnd.0 as isize));
+
+                // GetMessageW 出错时返回 -1，只有正数才继续循环
+                let mut msg = MSG::default();
+                while unsafe { GetMessageW(&mut msg, HWND::default(), 0, 0) }.0 > 0 {
+                    unsafe {
+                        let _ = TranslateMessage(&msg);
+                        DispatchMessageW(&msg);
+                    }
+                }
+                log_debug!("MessageWindow: message loop exited");
+            })
+            .map_err(|e| DeviceAwareError::Registration { guid: None, api: "CreateThread", error: Error::from(e) })?;
+
+        // 线程在发送之前退出 (例如 panic) 时按通用失败处理
+        let hwnd = match rx.recv() {
+            Ok(result) => result,
+            Err(_) => Err(("CreateWindowExW", Error::from(windows::Win32::Foundation::E_FAIL))),
+        };
+        match hwnd {
+            Ok(hwnd) => {
+                log_debug!("MessageWindow: created message-only window {:#x}", hwnd);
+                Ok(MessageWindow { hwnd, routes, thread: Some(thread) })
+            }
+            Err((api, error)) => {
+                log_error!("MessageWindow: {} failed: {:?}", api, error);
+                let _ = thread.join();
+                Err(DeviceAwareError::Registration { guid: None, api, error })
+            }
+        }
+    }
+
+    // 交给注册函数使用的窗口句柄，在 MessageWindow Drop 之前一直有效
+    pub(crate) fn hwnd(&self) -> HWND {
+        HWND(self.hwnd as _)
+    }
+
+    // 在消息线程上为 msg 调用 handler；同一消息可以有多个回调，按注册顺序调用
+    pub(crate) fn on_message(&self, msg: u32, handler: MessageHandler) {
+        self.routes.lock().unwrap_or_else(|e| e.into_inner()).push((msg, handler));
+    }
+}
+
+// 注册窗口类 (进程内只需一次，重复注册返回 ERROR_CLASS_ALREADY_EXISTS) 并创建纯消息窗口；
+// 失败时同时返回出错的函数名
+unsafe fn create_window() -> Result<HWND, (&'static str, Error)> {
+    let instance = unsafe { GetModuleHandleW(PCWSTR::null()) }.map_err(|e| ("GetModuleHandleW", e))?;
+    let class = WNDCLASSW {
+        lpfnWndProc: Some(window_proc),
+        hInstance: instance.into(),
+        lpszClassName: CLASS_NAME,
+        ..Default::default()
+    };
+    if unsafe { RegisterClassW(&class) } == 0 {
+        let error = unsafe { GetLastError() };
+        if error != ERROR_CLASS_ALREADY_EXISTS {
+            return Err(("RegisterClassW", Error::from(error.to_hresult())));
+        }
+    }
+    unsafe {
+        CreateWindowExW(
+            WINDOW_EX_STYLE::default(),
+            CLASS_NAME,
+            PCWSTR::null(),
+            WINDOW_STYLE::default(),
+            0,
+            0,
+            0,
+            0,
+            HWND_MESSAGE,
+            None,
+            instance,
+            None,
+        )
+    }
+    .map_err(|e| ("CreateWindowExW", e))
+}
+
+unsafe extern "system" fn window_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    match msg {
+        WM_CLOSE => {
+            let _ = unsafe { DestroyWindow(hwnd) };
+            return LRESULT(0);
+        }
+        WM_DESTROY => {
+            unsafe { PostQuitMessage(0) };
+            return LRESULT(0);
+        }
+        _ => {}
+    }
+
+    // 先复制出匹配的回调再调用，回调内部可以继续 on_message 而不会死锁
+    let handlers: Vec<MessageHandler> = ROUTES.with(|r| {
+        r.borrow()
+            .as_ref()
+            .map(|routes| {
+                routes
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .iter()
+                    .filter(|(m, _)| *m == msg)
+                    .map(|(_, h)| h.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    });
+    if handlers.is_empty() {
+        return unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) };
+    }
+    for handler in handlers {
+        guard_callback("MessageWindow", || handler(wparam, lparam));
+    }
+    // WM_POWERBROADCAST 与 WM_DEVICECHANGE 返回 TRUE 表示同意请求，其余消息返回 0
+    match msg {
+        WM_POWERBROADCAST | WM_DEVICECHANGE => LRESULT(1),
+        _ => LRESULT(0),
+    }
+}
+
+impl Drop for MessageWindow {
+    // 通知窗口在自己的线程上销毁，消息循环随之退出，再等待线程结束
+    fn drop(&mut self) {
+        let _ = unsafe { PostMessageW(self.hwnd(), WM_CLOSE, WPARAM(0), LPARAM(0)) };
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
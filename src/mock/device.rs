@@ -0,0 +1,91 @@
+// ============================================================================
+// mock 后端: DeviceNotifyObserver，通过 inject / inject_all 推送合成事件
+// ============================================================================
+
+use std::sync::{Arc, Mutex, Weak};
+
+use windows_core::{GUID, HRESULT};
+
+use crate::{DeviceAwareError, DeviceEvent};
+
+pub type DeviceEventCallback = Box<dyn Fn(DeviceEvent) + Send + Sync>;
+
+type DeviceEventFn = dyn Fn(DeviceEvent) + Send + Sync;
+
+type DeviceEventEntry = (GUID, Weak<DeviceEventFn>);
+
+static DEVICE_OBSERVERS: Mutex<Vec<DeviceEventEntry>> = Mutex::new(Vec::new());
+
+// fail_next_registration 预设的失败，按接口 GUID 各消费一次
+static FAILING_REGISTRATIONS: Mutex<Vec<(GUID, HRESULT)>> = Mutex::new(Vec::new());
+
+pub struct DeviceNotifyObserver {
+    interface_guid: GUID,
+    context: Option<Arc<DeviceEventFn>>,
+}
+
+impl DeviceNotifyObserver {
+    pub fn new<F>(interface_guid: GUID, handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(DeviceEvent) + Send + Sync + 'static
+    {
+        // 与真实后端的失败回滚一致: handler 在返回错误时释放
+        if let Some(error) = take_failure(interface_guid) {
+            log_error!("DeviceNotifyObserver(mock): simulated registration failure for {:?}", interface_guid);
+            return Err(DeviceAwareError::Registration {
+                guid: Some(interface_guid),
+                api: "CM_Register_Notification",
+                error: error.into(),
+            });
+        }
+        let context: Arc<DeviceEventFn> = Arc::new(handler);
+        let mut observers = DEVICE_OBSERVERS.lock().unwrap_or_else(|e| e.into_inner());
+        observers.retain(|(_, w)| w.strong_count() > 0);
+        observers.push((interface_guid, Arc::downgrade(&context)));
+        Ok(DeviceNotifyObserver { interface_guid, context: Some(context) })
+    }
+
+    // 让下一次针对 interface_guid 的注册以 error 失败，用于测试调用方的错误处理
+    pub fn fail_next_registration(interface_guid: GUID, error: HRESULT) {
+        FAILING_REGISTRATIONS.lock().unwrap_or_else(|e| e.into_inner()).push((interface_guid, error));
+    }
+
+    pub fn interface_guid(&self) -> GUID {
+        self.interface_guid
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.context.is_some()
+    }
+
+    pub fn unsubscribe(&mut self) {
+        self.context = None;
+    }
+
+    pub fn inject(&self, event: DeviceEvent) {
+        log_trace!("DeviceNotifyObserver(mock): inject {:?}", event);
+        if let Some(cb) = &self.context {
+            cb(event);
+        }
+    }
+
+    // 向所有订阅了 interface_guid 的存活观察者广播合成事件
+    pub fn inject_all(interface_guid: GUID, event: DeviceEvent) {
+        let observers: Vec<_> = DEVICE_OBSERVERS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .filter(|(g, _)| *g == interface_guid)
+            .filter_map(|(_, w)| w.upgrade())
+            .collect();
+        for cb in observers {
+            cb(event.clone());
+        }
+    }
+}
+
+fn take_failure(interface_guid: GUID) -> Option<HRESULT> {
+    let mut failures = FAILING_REGISTRATIONS.lock().unwrap_or_else(|e| e.into_inner());
+    let index = failures.iter().position(|(g, _)| *g == interface_guid)?;
+    Some(failures.remove(index).1)
+}
+
@@ -0,0 +1,594 @@
+// ============================================================================
+// mock 后端: 与真实 power_observer 保持相同的公开 API，但不调用任何 Win32 接口。
+// 测试通过 inject / inject_all 推送合成事件，回调在调用 inject 的线程上同步执行。
+// ============================================================================
+
+use std::fmt;
+use std::sync::mpsc::{self, Receiver};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, SystemTime};
+
+use windows_core::{GUID, HRESULT};
+
+use crate::change_signal::{ArrivalSignal, ChangeSignal};
+use crate::history::EventHistory;
+use crate::observer_ext::decode_notification;
+use crate::slow_callback::SlowCallbackWarning;
+use crate::{DeviceAwareError, EffectivePowerMode};
+
+// 与 windows::Win32::Foundation::E_NOTIMPL 相同
+pub(crate) const E_NOTIMPL: HRESULT = HRESULT(0x80004001_u32 as i32);
+
+const EFFECTIVE_POWER_MODE_V2: u32 = 2;
+
+// ============================================================================
+// 1. EffectiveModeObserver (mock)
+// ============================================================================
+
+pub type EffectiveModeCallback = Box<dyn Fn(EffectivePowerMode) + Send + Sync>;
+
+type EffectiveModeFn = dyn Fn(EffectivePowerMode, SystemTime) + Send + Sync;
+type EffectiveModeContext = Arc<EffectiveModeFn>;
+
+// 所有存活的 mock 观察者，供 inject_all 广播
+static EFFECTIVE_MODE_OBSERVERS: Mutex<Vec<Weak<EffectiveModeFn>>> = Mutex::new(Vec::new());
+
+// 最近一次 inject_all 的模式；与真实系统一致，新注册的观察者会立即收到一次当前模式
+static CURRENT_MODE: Mutex<Option<EffectivePowerMode>> = Mutex::new(None);
+
+pub struct EffectiveModeObserver {
+    context: Option<EffectiveModeContext>,
+    slow: Arc<SlowCallbackWarning>,
+}
+
+impl EffectiveModeObserver {
+    pub fn new<F>(handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(EffectivePowerMode) + Send + Sync + 'static
+    {
+        Self::new_timestamped(move |mode, _at| handler(mode))
+    }
+
+    pub fn new_timestamped<F>(handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(EffectivePowerMode, SystemTime) + Send + Sync + 'static
+    {
+        let slow = Arc::new(SlowCallbackWarning::default());
+        let timer = slow.clone();
+        let context: EffectiveModeContext = Arc::new(move |mode, at| {
+            timer.time("EffectiveModeObserver", || handler(mode, at));
+        });
+        {
+            let mut observers = EFFECTIVE_MODE_OBSERVERS.lock().unwrap_or_else(|e| e.into_inner());
+            observers.retain(|w| w.strong_count() > 0);
+            observers.push(Arc::downgrade(&context));
+        }
+        let current = *CURRENT_MODE.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(mode) = current {
+            context(mode, SystemTime::now());
+        }
+        Ok(EffectiveModeObserver { context: Some(context), slow })
+    }
+
+    pub fn new_channel() -> Result<(Self, Receiver<EffectivePowerMode>), DeviceAwareError> {
+        let (tx, rx) = mpsc::channel();
+        let observer = Self::new(move |mode| {
+            let _ = tx.send(mode);
+        })?;
+        Ok((observer, rx))
+    }
+
+    pub fn version(&self) -> u32 {
+        EFFECTIVE_POWER_MODE_V2
+    }
+
+    pub fn with_slow_callback_warning(self, threshold: Duration) -> Self {
+        self.slow.set_threshold(threshold);
+        self
+    }
+
+    /// mock 后端没有系统注册，始终返回空句柄
+    ///
+    /// # Safety
+    /// 与真实后端相同: 不要自行注销该句柄
+    #[cfg(windows)]
+    pub unsafe fn raw_handle(&self) -> windows::Win32::Foundation::HANDLE {
+        windows::Win32::Foundation::HANDLE::default()
+    }
+
+    pub fn unsubscribe(&mut self) {
+        self.context = None;
+    }
+
+    // 向当前观察者推送一个合成事件
+    pub fn inject(&self, mode: EffectivePowerMode) {
+        log_trace!("EffectiveModeObserver(mock): inject {:?}", mode);
+        if let Some(cb) = &self.context {
+            cb(mode, SystemTime::now());
+        }
+    }
+
+    // 向所有存活的 EffectiveModeObserver 广播合成事件 (例如 PowerMonitor 内部持有的观察者)
+    pub fn inject_all(mode: EffectivePowerMode) {
+        *CURRENT_MODE.lock().unwrap_or_else(|e| e.into_inner()) = Some(mode);
+        let at = SystemTime::now();
+        let observers: Vec<_> = EFFECTIVE_MODE_OBSERVERS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .filter_map(Weak::upgrade)
+            .collect();
+        // 不持锁调用回调，允许回调内部再创建/注销观察者
+        for cb in observers {
+            cb(mode, at);
+        }
+    }
+}
+
+impl fmt::Debug for EffectiveModeObserver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EffectiveModeObserver")
+            .field("active", &self.context.is_some())
+            .field("version", &EFFECTIVE_POWER_MODE_V2)
+            .finish()
+    }
+}
+
+// ============================================================================
+// 2. PowerSettingObserver (mock)
+// ============================================================================
+
+pub type PowerSettingCallback = Box<dyn Fn(u32) + Send + Sync>;
+
+pub type RawPowerSettingCallback = Box<dyn Fn(&[u8]) + Send + Sync>;
+
+type PowerSettingFn = dyn Fn(&[u8], SystemTime) + Send + Sync;
+type PowerSettingContext = Arc<PowerSettingFn>;
+
+type PowerSettingEntry = (GUID, Weak<PowerSettingFn>);
+
+static POWER_SETTING_OBSERVERS: Mutex<Vec<PowerSettingEntry>> = Mutex::new(Vec::new());
+
+// 每个 GUID 最近一次 inject_all 的载荷；与真实系统一致，新注册的观察者会立即收到一次当前值
+static CURRENT_VALUES: Mutex<Vec<(GUID, Vec<u8>)>> = Mutex::new(Vec::new());
+
+// fail_next_registration 预设的失败，按 GUID 各消费一次
+static FAILING_REGISTRATIONS: Mutex<Vec<(GUID, HRESULT)>> = Mutex::new(Vec::new());
+
+pub struct PowerSettingObserver {
+    guid: GUID,
+    context: Option<PowerSettingContext>,
+    change: Arc<ChangeSignal>,
+    arrived: Arc<ArrivalSignal>,
+    history: Arc<EventHistory>,
+    paused: Arc<AtomicBool>,
+    slow: Arc<SlowCallbackWarning>,
+}
+
+impl PowerSettingObserver {
+    pub fn new<F>(guid: GUID, handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(u32) + Send + Sync + 'static
+    {
+        Self::new_timestamped(guid, move |val, _at| handler(val))
+    }
+
+    pub fn new_timestamped<F>(guid: GUID, handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(u32, SystemTime) + Send + Sync + 'static
+    {
+        let change = Arc::new(ChangeSignal::default());
+        let signal = change.clone();
+        let history = Arc::new(EventHistory::default());
+        let events = history.clone();
+
+        // 与真实后端共用解码与异常记录
+        Self::register(guid, change, history, Arc::new(move |data: &[u8], at| {
+            if let Some(val) = decode_notification(guid, data) {
+                events.push(at, val);
+                handler(val, at);
+                signal.notify(val);
+            }
+        }))
+    }
+
+    pub fn new_raw<F>(guid: GUID, handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(&[u8]) + Send + Sync + 'static
+    {
+        Self::register(guid, Arc::default(), Arc::default(), Arc::new(move |data: &[u8], _at| handler(data)))
+    }
+
+    fn register(
+        guid: GUID,
+        change: Arc<ChangeSignal>,
+        history: Arc<EventHistory>,
+        callback: PowerSettingContext,
+    ) -> Result<Self, DeviceAwareError> {
+        // 与真实后端的失败回滚一致: 回调在这里随 callback 一起释放
+        if let Some(error) = take_failure(guid) {
+            log_error!("PowerSettingObserver(mock): simulated registration failure for GUID {:?}", guid);
+            return Err(DeviceAwareError::Registration {
+                guid: Some(guid),
+                api: "RegisterPowerSettingNotification",
+                error: error.into(),
+            });
+        }
+        let paused = Arc::new(AtomicBool::new(false));
+        let flag = paused.clone();
+        let slow = Arc::new(SlowCallbackWarning::default());
+        let timer = slow.clone();
+        let arrived = Arc::new(ArrivalSignal::default());
+        let signal = arrived.clone();
+        let context: PowerSettingContext = Arc::new(move |data: &[u8], at| {
+            signal.set();
+            if !flag.load(Ordering::Acquire) {
+                timer.time("PowerSettingObserver", || callback(data, at));
+            }
+        });
+        {
+            let mut observers = POWER_SETTING_OBSERVERS.lock().unwrap_or_else(|e| e.into_inner());
+            observers.retain(|(_, w)| w.strong_count() > 0);
+            observers.push((guid, Arc::downgrade(&context)));
+        }
+        let current = CURRENT_VALUES
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .find(|(g, _)| *g == guid)
+            .map(|(_, data)| data.clone());
+        if let Some(data) = current {
+            context(&data, SystemTime::now());
+        }
+        Ok(PowerSettingObserver {
+            guid,
+            context: Some(context),
+            change,
+            arrived,
+            history,
+            paused,
+            slow,
+        })
+    }
+
+    // 让下一次针对 guid 的注册以 error 失败，用于测试调用方的错误处理
+    pub fn fail_next_registration(guid: GUID, error: HRESULT) {
+        FAILING_REGISTRATIONS.lock().unwrap_or_else(|e| e.into_inner()).push((guid, error));
+    }
+
+    // 窗口句柄注册在 mock 后端中没有回调可注入，返回的观察者只用于占位
+    #[cfg(windows)]
+    pub fn new_for_hwnd(_hwnd: windows::Win32::Foundation::HWND, guid: GUID) -> Result<Self, DeviceAwareError> {
+        Ok(PowerSettingObserver {
+            guid,
+            context: None,
+            change: Arc::default(),
+            arrived: Arc::default(),
+            history: Arc::default(),
+            paused: Arc::default(),
+            slow: Arc::default(),
+        })
+    }
+
+    // 同 new_for_hwnd，只用于占位
+    #[cfg(windows)]
+    pub fn new_for_service_handle(
+        _status_handle: windows::Win32::System::Services::SERVICE_STATUS_HANDLE,
+        guid: GUID,
+    ) -> Result<Self, DeviceAwareError> {
+        Ok(PowerSettingObserver {
+            guid,
+            context: None,
+            change: Arc::default(),
+            arrived: Arc::default(),
+            history: Arc::default(),
+            paused: Arc::default(),
+            slow: Arc::default(),
+        })
+    }
+
+    pub fn new_channel(guid: GUID) -> Result<(Self, Receiver<u32>), DeviceAwareError> {
+        let (tx, rx) = mpsc::channel();
+        let observer = Self::new(guid, move |val| {
+            let _ = tx.send(val);
+        })?;
+        Ok((observer, rx))
+    }
+
+    // mock 后端没有系统状态可查询
+    pub fn current_value(&self) -> Result<u32, DeviceAwareError> {
+        Err(DeviceAwareError::Unsupported)
+    }
+
+    pub fn guid(&self) -> GUID {
+        self.guid
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.context.is_some()
+    }
+
+    /// mock 后端没有系统注册，始终返回 None
+    ///
+    /// # Safety
+    /// 与真实后端相同: 不要自行注销该句柄
+    #[cfg(windows)]
+    pub unsafe fn raw_handle(&self) -> Option<windows::Win32::System::Power::HPOWERNOTIFY> {
+        None
+    }
+
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::Release);
+    }
+
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::Release);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Acquire)
+    }
+
+    pub fn wait_for_change(&self, timeout: Duration) -> Option<u32> {
+        self.change.wait(timeout)
+    }
+
+    // 与真实后端相同: timeout 内 (已经注入过时立即返回) 没有收到任何注入的通知时记录警告并返回 false
+    pub fn verify_notifications(&self, timeout: Duration) -> bool {
+        if self.arrived.wait(timeout) {
+            return true;
+        }
+        log_warn!(
+            "PowerSettingObserver(mock): no notification for GUID {:?} within {:?} of registration (synchronous query: {:?}); \
+             notifications appear non-functional, consider polling current_value instead",
+            self.guid, timeout, self.current_value()
+        );
+        false
+    }
+
+    pub fn with_history(self, capacity: usize) -> Self {
+        self.history.set_capacity(capacity);
+        self
+    }
+
+    pub fn history(&self) -> Vec<(SystemTime, u32)> {
+        self.history.snapshot()
+    }
+
+    pub fn with_slow_callback_warning(self, threshold: Duration) -> Self {
+        self.slow.set_threshold(threshold);
+        self
+    }
+
+    pub fn unsubscribe(&mut self) {
+        self.context = None;
+    }
+
+    // 向当前观察者推送一个 u32 合成事件
+    pub fn inject(&self, value: u32) {
+        self.inject_raw(&value.to_ne_bytes());
+    }
+
+    // 向当前观察者推送任意载荷
+    pub fn inject_raw(&self, data: &[u8]) {
+        log_trace!("PowerSettingObserver(mock): GUID {:?} <- {:?}", self.guid, data);
+        if let Some(cb) = &self.context {
+            cb(data, SystemTime::now());
+        }
+    }
+
+    // 向所有订阅了 guid 的存活观察者广播 u32 合成事件
+    pub fn inject_all(guid: GUID, value: u32) {
+        Self::inject_all_raw(guid, &value.to_ne_bytes());
+    }
+
+    pub fn inject_all_raw(guid: GUID, data: &[u8]) {
+        {
+            let mut current = CURRENT_VALUES.lock().unwrap_or_else(|e| e.into_inner());
+            current.retain(|(g, _)| *g != guid);
+            current.push((guid, data.to_vec()));
+        }
+        let at = SystemTime::now();
+        let observers: Vec<_> = POWER_SETTING_OBSERVERS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .filter(|(g, _)| *g == guid)
+            .filter_map(|(_, w)| w.upgrade())
+            .collect();
+        for cb in observers {
+            cb(data, at);
+        }
+    }
+}
+
+fn take_failure(guid: GUID) -> Option<HRESULT> {
+    let mut failures = FAILING_REGISTRATIONS.lock().unwrap_or_else(|e| e.into_inner());
+    let index = failures.iter().position(|(g, _)| *g == guid)?;
+    Some(failures.remove(index).1)
+}
+
+impl fmt::Debug for PowerSettingObserver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PowerSettingObserver")
+            .field("guid", &self.guid)
+            .field("active", &self.is_active())
+            .field("paused", &self.is_paused())
+            .finish()
+    }
+}
+
+// 测试共用 mock 的全局状态 (当前值、inject_all 广播、系统状态)，依赖这些状态的测试经由它串行执行；
+// 取得锁时清空上一个测试留下的当前值与系统状态
+#[cfg(test)]
+pub(crate) fn lock_global_state() -> std::sync::MutexGuard<'static, ()> {
+    static LOCK: Mutex<()> = Mutex::new(());
+    let guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    CURRENT_VALUES.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    *CURRENT_MODE.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    crate::status::set_system_power_status(None);
+    guard
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+
+    use super::*;
+
+    // 观察者可以移动到其他线程并在那里释放
+    #[test]
+    fn observers_move_across_threads() {
+        let guid = GUID::from_u128(0x6d3b7a10_47a1_4c55_9e2c_1f0a5b6c0004);
+        // 回调持有 marker 的一份引用，观察者释放后只剩这里的一份
+        let marker = Arc::new(());
+        let held = marker.clone();
+        let (tx, rx) = mpsc::channel();
+        let setting = PowerSettingObserver::new(guid, move |val| {
+            let _ = &held;
+            let _ = tx.send(val);
+        })
+        .unwrap();
+        let mode = EffectiveModeObserver::new(|_| {}).unwrap();
+        std::thread::spawn(move || {
+            setting.inject(1);
+            mode.inject(EffectivePowerMode::Balanced);
+            drop(setting);
+            drop(mode);
+        })
+        .join()
+        .unwrap();
+        assert_eq!(rx.try_recv(), Ok(1));
+        assert_eq!(Arc::strong_count(&marker), 1);
+    }
+
+    // 两个 EffectiveModeObserver 各自持有回调，互不影响
+    #[test]
+    fn effective_mode_observers_fire_independently() {
+        let _serial = lock_global_state();
+        let (first, first_rx) = EffectiveModeObserver::new_channel().unwrap();
+        let (second, second_rx) = EffectiveModeObserver::new_channel().unwrap();
+        // 丢弃注册时补发的当前模式
+        while first_rx.try_recv().is_ok() {}
+        while second_rx.try_recv().is_ok() {}
+
+        EffectiveModeObserver::inject_all(EffectivePowerMode::BetterPerformance);
+        assert_eq!(first_rx.try_recv(), Ok(EffectivePowerMode::BetterPerformance));
+        assert_eq!(second_rx.try_recv(), Ok(EffectivePowerMode::BetterPerformance));
+
+        drop(first);
+        EffectiveModeObserver::inject_all(EffectivePowerMode::BatterySaver);
+        assert_eq!(second_rx.try_recv(), Ok(EffectivePowerMode::BatterySaver));
+        assert!(first_rx.try_recv().is_err());
+        drop(second);
+    }
+
+    // "只处理一次": 回调内注销自己的观察者，不应死锁，之后也不再被调用
+    #[test]
+    fn handler_can_unsubscribe_itself() {
+        let guid = GUID::from_u128(0x6d3b7a10_47a1_4c55_9e2c_1f0a5b6c0006);
+        let calls = Arc::new(AtomicUsize::new(0));
+        let slot: Arc<Mutex<Option<PowerSettingObserver>>> = Arc::default();
+
+        let counter = calls.clone();
+        let own = Arc::downgrade(&slot);
+        let observer = PowerSettingObserver::new(guid, move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            if let Some(slot) = own.upgrade()
+                && let Some(observer) = slot.lock().unwrap().as_mut()
+            {
+                observer.unsubscribe();
+            }
+        })
+        .unwrap();
+        *slot.lock().unwrap() = Some(observer);
+
+        let (done_tx, done_rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            PowerSettingObserver::inject_all(guid, 1);
+            PowerSettingObserver::inject_all(guid, 0);
+            let _ = done_tx.send(());
+        });
+        assert!(done_rx.recv_timeout(Duration::from_secs(5)).is_ok(), "self-unsubscribe deadlocked");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert!(!slot.lock().unwrap().as_ref().unwrap().is_active());
+    }
+
+    // 长度不是 4 字节的载荷被丢弃 (记录警告)，不会被当作 0 交给回调
+    #[test]
+    fn wrong_length_payload_is_not_decoded_as_zero() {
+        let guid = GUID::from_u128(0x6d3b7a10_47a1_4c55_9e2c_1f0a5b6c0007);
+        let (tx, rx) = mpsc::channel();
+        let observer = PowerSettingObserver::new_timestamped(guid, move |val, _at| {
+            let _ = tx.send(val);
+        })
+        .unwrap();
+
+        observer.inject_raw(&[0, 0]);
+        observer.inject_raw(&[0; 8]);
+        observer.inject_raw(&[]);
+        assert!(rx.try_recv().is_err());
+
+        observer.inject(7);
+        assert_eq!(rx.try_recv(), Ok(7));
+    }
+
+    // 捕获警告级别的日志；所有测试共用同一个 logger，断言时按 GUID 过滤
+    #[cfg(feature = "logging")]
+    struct WarningCapture(Mutex<Vec<String>>);
+
+    #[cfg(feature = "logging")]
+    impl log::Log for WarningCapture {
+        fn enabled(&self, metadata: &log::Metadata) -> bool {
+            metadata.level() <= log::Level::Warn
+        }
+
+        fn log(&self, record: &log::Record) {
+            if self.enabled(record.metadata()) {
+                self.0.lock().unwrap_or_else(|e| e.into_inner()).push(record.args().to_string());
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    #[cfg(feature = "logging")]
+    static WARNINGS: WarningCapture = WarningCapture(Mutex::new(Vec::new()));
+
+    #[cfg(feature = "logging")]
+    fn warnings_for(guid: GUID) -> Vec<String> {
+        let tag = format!("{:?}", guid);
+        WARNINGS
+            .0
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .filter(|message| message.contains(&tag))
+            .cloned()
+            .collect()
+    }
+
+    // 两个后端共用的解码路径: 空载荷与长度不符的载荷各记录一条异常，正常载荷不记录
+    #[cfg(feature = "logging")]
+    #[test]
+    fn malformed_payloads_are_logged() {
+        let _ = log::set_logger(&WARNINGS);
+        log::set_max_level(log::LevelFilter::Warn);
+
+        let guid = GUID::from_u128(0x6d3b7a10_47a1_4c55_9e2c_1f0a5b6c0008);
+        assert_eq!(decode_notification(guid, &[]), None);
+        assert_eq!(decode_notification(guid, &[0; 3]), None);
+        assert_eq!(decode_notification(guid, &9u32.to_ne_bytes()), Some(9));
+        let warnings = warnings_for(guid);
+        assert_eq!(warnings.len(), 2, "{:?}", warnings);
+        assert!(warnings[0].contains("empty payload"));
+        assert!(warnings[1].contains("3-byte payload"));
+
+        // mock 观察者走同一条路径
+        let guid = GUID::from_u128(0x6d3b7a10_47a1_4c55_9e2c_1f0a5b6c0009);
+        let observer = PowerSettingObserver::new(guid, |_| {}).unwrap();
+        observer.inject_raw(&[]);
+        observer.inject_raw(&[0; 8]);
+        observer.inject(1);
+        let warnings = warnings_for(guid);
+        assert_eq!(warnings.len(), 2, "{:?}", warnings);
+        assert!(warnings[0].contains("empty payload"));
+        assert!(warnings[1].contains("8-byte payload"));
+    }
+}
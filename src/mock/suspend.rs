@@ -0,0 +1,72 @@
+// ============================================================================
+// mock 后端: SuspendResumeObserver，通过 inject / inject_all 推送合成事件
+// ============================================================================
+
+use std::sync::{Arc, Mutex, Weak};
+
+use windows_core::HRESULT;
+
+use crate::{DeviceAwareError, SystemPowerEvent};
+
+pub type SuspendResumeCallback = Box<dyn Fn(SystemPowerEvent) + Send + Sync>;
+
+type SuspendResumeFn = dyn Fn(SystemPowerEvent) + Send + Sync;
+
+static SUSPEND_RESUME_OBSERVERS: Mutex<Vec<Weak<SuspendResumeFn>>> = Mutex::new(Vec::new());
+
+// fail_next_registration 预设的失败，只消费一次
+static FAILING_REGISTRATION: Mutex<Option<HRESULT>> = Mutex::new(None);
+
+pub struct SuspendResumeObserver {
+    context: Option<Arc<SuspendResumeFn>>,
+}
+
+impl SuspendResumeObserver {
+    pub fn new<F>(handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(SystemPowerEvent) + Send + Sync + 'static
+    {
+        // 与真实后端的失败回滚一致: handler 在返回错误时释放
+        if let Some(error) = FAILING_REGISTRATION.lock().unwrap_or_else(|e| e.into_inner()).take() {
+            log_error!("SuspendResumeObserver(mock): simulated registration failure");
+            return Err(DeviceAwareError::Registration {
+                guid: None,
+                api: "PowerRegisterSuspendResumeNotification",
+                error: error.into(),
+            });
+        }
+        let context: Arc<SuspendResumeFn> = Arc::new(handler);
+        let mut observers = SUSPEND_RESUME_OBSERVERS.lock().unwrap_or_else(|e| e.into_inner());
+        observers.retain(|w| w.strong_count() > 0);
+        observers.push(Arc::downgrade(&context));
+        Ok(SuspendResumeObserver { context: Some(context) })
+    }
+
+    // 让下一次注册以 error 失败，用于测试调用方的错误处理
+    pub fn fail_next_registration(error: HRESULT) {
+        *FAILING_REGISTRATION.lock().unwrap_or_else(|e| e.into_inner()) = Some(error);
+    }
+
+    pub fn unsubscribe(&mut self) {
+        self.context = None;
+    }
+
+    pub fn inject(&self, event: SystemPowerEvent) {
+        log_trace!("SuspendResumeObserver(mock): inject {:?}", event);
+        if let Some(cb) = &self.context {
+            cb(event);
+        }
+    }
+
+    pub fn inject_all(event: SystemPowerEvent) {
+        let observers: Vec<_> = SUSPEND_RESUME_OBSERVERS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .filter_map(Weak::upgrade)
+            .collect();
+        for cb in observers {
+            cb(event);
+        }
+    }
+}
+
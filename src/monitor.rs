@@ -0,0 +1,892 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, SystemTime};
+
+use windows_core::GUID;
+
+use crate::batch::Batcher;
+use crate::dispatch::Dispatcher;
+use crate::history::EventHistory;
+use crate::guids::{
+    GUID_ACDC_POWER_SOURCE, GUID_BATTERY_PERCENTAGE_REMAINING, GUID_POWER_SAVING_STATUS,
+};
+use crate::observer_ext::Debouncer;
+use crate::watchdog::Watchdog;
+use crate::{
+    system_power_status, DeviceAwareError, EffectiveModeObserver, EffectivePowerMode,
+    PowerSettingObserver, PowerSourceType, PowerState, SystemPowerStatus,
+};
+
+// ============================================================================
+// PowerMonitor: 统一持有所有观察者，并通过单一回调分发事件
+// ============================================================================
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+// 相邻标签: {"kind":"effective_mode","value":"balanced"}，即 NDJSON 录制格式中 kind / value 两个字段
+#[cfg_attr(feature = "serde", serde(tag = "kind", content = "value", rename_all = "snake_case"))]
+pub enum PowerEvent {
+    EffectiveMode(EffectivePowerMode),
+    PowerSource(PowerSourceType),
+    SaverStatus(bool),
+    BatteryPercent(u32),
+}
+
+impl PowerEvent {
+    // 事件类别的下标，用于按类别保存独立状态 (去抖、启动过滤)
+    fn category(self) -> usize {
+        match self {
+            PowerEvent::EffectiveMode(_) => 0,
+            PowerEvent::PowerSource(_) => 1,
+            PowerEvent::SaverStatus(_) => 2,
+            PowerEvent::BatteryPercent(_) => 3,
+        }
+    }
+}
+
+type PowerEventHandler = Arc<dyn Fn(PowerEvent) + Send + Sync>;
+
+// with_initial_snapshot(false) 时使用: 每个类别第一次得到的值只作为基准 (包括注册后系统
+// 立即推送的当前值)，之后出现与基准不同的值才开始分发，此后该类别不再过滤
+#[derive(Default)]
+struct StartupFilter {
+    settled: [AtomicBool; 4],
+    // 节电模式是 bool，缓存里的初始 false 无法与真实读数区分，基准单独记录
+    saver_baseline: Mutex<Option<bool>>,
+}
+
+impl StartupFilter {
+    // previous 为应用本事件之前的缓存状态
+    fn should_deliver(&self, previous: &PowerState, event: PowerEvent) -> bool {
+        let settled = &self.settled[event.category()];
+        if settled.load(Ordering::Acquire) {
+            return true;
+        }
+        let changed = match event {
+            // 滑块位置没有同步查询，缓存中还没有值时说明这是系统推送的当前值
+            PowerEvent::EffectiveMode(mode) => previous.effective_mode.is_some_and(|m| m != mode),
+            PowerEvent::PowerSource(source) => {
+                previous.source != PowerSourceType::Unknown && previous.source != source
+            }
+            PowerEvent::SaverStatus(is_on) => {
+                let mut baseline = self.saver_baseline.lock().unwrap_or_else(|e| e.into_inner());
+                baseline.replace(is_on).is_some_and(|was| was != is_on)
+            }
+            PowerEvent::BatteryPercent(percent) => {
+                previous.battery_percent.is_some_and(|p| u32::from(p) != percent.min(100))
+            }
+        };
+        if changed {
+            settled.store(true, Ordering::Release);
+        }
+        changed
+    }
+
+    // 启动时同步查询到的节电模式作为基准 (在注册之前，系统推送总在其后到达)
+    fn seed_saver(&self, is_on: bool) {
+        *self.saver_baseline.lock().unwrap_or_else(|e| e.into_inner()) = Some(is_on);
+    }
+}
+
+// 一组实际注册的观察者；watchdog 重新注册时整体替换
+struct Registrations {
+    // 仅用于保持注册存活，Drop 时自动注销
+    _effective_mode: Option<EffectiveModeObserver>,
+    _settings: Vec<PowerSettingObserver>,
+}
+
+pub struct PowerMonitor {
+    // 字段按声明顺序 Drop: 先停止 watchdog 线程，再注销观察者
+    _watchdog: Option<Watchdog>,
+    _registrations: Arc<Mutex<Registrations>>,
+    // with_dedicated_thread 的投递线程；同样在观察者注销之后结束
+    _dispatcher: Option<Dispatcher>,
+    // 观察者注销后批处理线程的发送端随之丢弃，这里等待剩余事件投递完毕
+    _batcher: Option<Batcher>,
+    state: Arc<Mutex<PowerState>>,
+    // 每次更新 state 后唤醒 run_until
+    state_changed: Arc<Condvar>,
+    log: Arc<EventHistory<PowerEvent>>,
+    // 构造之后通过 subscribe_additional 追加的订阅
+    additional: Mutex<Vec<(AdditionalSubscription, PowerSettingObserver)>>,
+    // subscribe 的共享注册: 每个 GUID 只有一个系统注册
+    shared: Mutex<HashMap<GUID, SharedRegistration>>,
+    next_id: AtomicU64,
+}
+
+// subscribe_additional 返回的句柄，用于之后单独移除该订阅
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct AdditionalSubscription(u64);
+
+// run_until 超时: 携带超时时刻的状态
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct WaitTimeout(pub PowerState);
+
+impl fmt::Display for WaitTimeout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("timed out waiting for power state condition")
+    }
+}
+
+impl std::error::Error for WaitTimeout {}
+
+// subscribe 返回的句柄，用于之后通过 unsubscribe 移除该回调
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct SubscriptionId(u64);
+
+// 同一 GUID 的全部逻辑订阅者，以及最近一次通知的值 (系统只在注册时推送一次当前值，
+// 之后加入的订阅者由这里补发)
+#[derive(Default)]
+struct SharedSubscribers {
+    handlers: HashMap<SubscriptionId, Arc<dyn Fn(u32) + Send + Sync>>,
+    last: Option<u32>,
+}
+
+type SharedHandlers = Arc<Mutex<SharedSubscribers>>;
+
+// 同一 GUID 的一个系统注册及其全部逻辑订阅者；订阅者数即引用计数，归零时注销
+struct SharedRegistration {
+    _observer: PowerSettingObserver,
+    handlers: SharedHandlers,
+}
+
+impl PowerMonitor {
+    // 订阅滑块、AC/DC 与节电模式三类事件
+    pub fn start<F>(handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(PowerEvent) + Send + Sync + 'static
+    {
+        PowerMonitorBuilder::new()
+            .with_effective_mode()
+            .with_power_source()
+            .with_saver_status()
+            .build(handler)
+    }
+
+    // 运行中追加订阅一个 GUID；观察者由 monitor 持有，monitor Drop 时一并注销
+    pub fn subscribe_additional<F>(&self, guid: GUID, handler: F) -> Result<AdditionalSubscription, DeviceAwareError>
+    where F: Fn(u32) + Send + Sync + 'static
+    {
+        let observer = PowerSettingObserver::new(guid, handler)?;
+        let id = AdditionalSubscription(self.next_id.fetch_add(1, Ordering::Relaxed));
+        self.additional.lock().unwrap_or_else(|e| e.into_inner()).push((id, observer));
+        Ok(id)
+    }
+
+    // 注销并移除一个追加的订阅；id 不存在 (例如已移除) 时返回 false
+    pub fn remove_additional(&self, id: AdditionalSubscription) -> bool {
+        let removed = {
+            let mut additional = self.additional.lock().unwrap_or_else(|e| e.into_inner());
+            additional
+                .iter()
+                .position(|(sub, _)| *sub == id)
+                .map(|index| additional.swap_remove(index))
+        };
+        // 在锁外 Drop，注销期间不阻塞其他调用
+        removed.is_some()
+    }
+
+    // 动态添加回调 (例如插件)。同一 GUID 的所有订阅者共用一个系统注册，通知到达时依次调用
+    // 各个回调 (顺序不保证)；第一个订阅者会创建注册，最后一个订阅者移除时注销。
+    // 与单独注册一样，每个订阅者都会先收到一次当前值: 第一个订阅者来自注册后系统的推送，
+    // 之后加入的订阅者由最近一次通知的值立即补发 (尚未收到任何通知时等系统推送)
+    pub fn subscribe<F>(&self, guid: GUID, handler: F) -> Result<SubscriptionId, DeviceAwareError>
+    where F: Fn(u32) + Send + Sync + 'static
+    {
+        let id = SubscriptionId(self.next_id.fetch_add(1, Ordering::Relaxed));
+        // 补发可能与新的通知并发: 新通知已经送达该订阅者时不再补发旧值
+        let primed = Arc::new(AtomicBool::new(false));
+        let handler = Arc::new(handler);
+        let subscriber: Arc<dyn Fn(u32) + Send + Sync> = {
+            let primed = primed.clone();
+            let handler = handler.clone();
+            Arc::new(move |val| {
+                primed.store(true, Ordering::Release);
+                handler(val);
+            })
+        };
+
+        let mut shared = self.shared.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(registration) = shared.get(&guid) {
+            let last = {
+                let mut subscribers = registration.handlers.lock().unwrap_or_else(|e| e.into_inner());
+                subscribers.handlers.insert(id, subscriber);
+                subscribers.last
+            };
+            drop(shared);
+            // 在锁外补发，回调内部可以 subscribe / unsubscribe
+            if let Some(val) = last
+                && !primed.swap(true, Ordering::AcqRel)
+            {
+                handler(val);
+            }
+            return Ok(id);
+        }
+
+        // 先放入回调再注册，注册后系统立即推送的当前值也能送达第一个订阅者
+        let handlers: SharedHandlers = Arc::default();
+        handlers.lock().unwrap_or_else(|e| e.into_inner()).handlers.insert(id, subscriber);
+        let fan_out = handlers.clone();
+        let observer = PowerSettingObserver::new(guid, move |val| {
+            // 复制出回调后在锁外调用，回调内部可以 subscribe / unsubscribe
+            let handlers: Vec<_> = {
+                let mut subscribers = fan_out.lock().unwrap_or_else(|e| e.into_inner());
+                subscribers.last = Some(val);
+                subscribers.handlers.values().cloned().collect()
+            };
+            for handler in handlers {
+                handler(val);
+            }
+        })?;
+        shared.insert(guid, SharedRegistration { _observer: observer, handlers });
+        Ok(id)
+    }
+
+    // 移除一个 subscribe 添加的回调；id 不存在 (例如已移除) 时返回 false。
+    // 可以在回调内部调用，包括移除最后一个订阅者
+    pub fn unsubscribe(&self, id: SubscriptionId) -> bool {
+        let mut released = None;
+        let removed = {
+            let mut shared = self.shared.lock().unwrap_or_else(|e| e.into_inner());
+            let mut found = None;
+            for (guid, registration) in shared.iter() {
+                let mut subscribers = registration.handlers.lock().unwrap_or_else(|e| e.into_inner());
+                if subscribers.handlers.remove(&id).is_some() {
+                    found = Some((*guid, subscribers.handlers.is_empty()));
+                    break;
+                }
+            }
+            if let Some((guid, true)) = found {
+                released = shared.remove(&guid);
+            }
+            found.is_some()
+        };
+        // 与 remove_additional 相同，在锁外 Drop 注销
+        drop(released);
+        removed
+    }
+
+    // 事件日志中晚于 since 的事件 (按时间先后)，用于界面从后台恢复后补上错过的事件。
+    // 传入上次处理的最后一个事件的时间即可避免重复。日志容量有限 (见 with_event_log)，
+    // 写满后丢弃最早的事件，因此离开太久时返回的只是最近的一部分
+    pub fn events_since(&self, since: SystemTime) -> Vec<(SystemTime, PowerEvent)> {
+        self.log.since(since)
+    }
+
+    // 当前各项读数: 构造时同步查询一次，之后随每个事件更新，不依赖任何已发生的变化
+    pub fn current(&self) -> PowerState {
+        *self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    // 阻塞当前线程，直到缓存状态满足 predicate 或超时，适合测试与脚本 (例如等待拔掉电源)。
+    // 先用当前状态检查一次，之后每次事件更新状态后再检查；事件照常分发给回调。
+    // predicate 在持有状态锁时调用，不要在其中调用本 monitor 的方法
+    pub fn run_until<F>(&self, predicate: F, timeout: Duration) -> Result<PowerState, WaitTimeout>
+    where F: Fn(&PowerState) -> bool
+    {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let (state, result) = self
+            .state_changed
+            .wait_timeout_while(state, timeout, |state| !predicate(state))
+            .unwrap_or_else(|e| e.into_inner());
+        if result.timed_out() { Err(WaitTimeout(*state)) } else { Ok(*state) }
+    }
+}
+
+// ============================================================================
+// PowerMonitorBuilder: 按需选择要订阅的事件类别
+// ============================================================================
+
+// 事件日志的默认容量
+const DEFAULT_EVENT_LOG_CAPACITY: usize = 256;
+
+#[derive(Debug, Clone)]
+pub struct PowerMonitorBuilder {
+    effective_mode: bool,
+    power_source: bool,
+    saver_status: bool,
+    battery_percent: bool,
+    debounce: Option<Duration>,
+    watchdog: Option<Duration>,
+    batch_window: Option<Duration>,
+    dedicated_thread: bool,
+    slow_callback_warning: Option<Duration>,
+    event_log: usize,
+    initial_snapshot: bool,
+}
+
+impl Default for PowerMonitorBuilder {
+    fn default() -> Self {
+        PowerMonitorBuilder {
+            effective_mode: false,
+            power_source: false,
+            saver_status: false,
+            battery_percent: false,
+            debounce: None,
+            watchdog: None,
+            batch_window: None,
+            dedicated_thread: false,
+            slow_callback_warning: None,
+            event_log: DEFAULT_EVENT_LOG_CAPACITY,
+            initial_snapshot: true,
+        }
+    }
+}
+
+impl PowerMonitorBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_effective_mode(mut self) -> Self {
+        self.effective_mode = true;
+        self
+    }
+
+    pub fn with_power_source(mut self) -> Self {
+        self.power_source = true;
+        self
+    }
+
+    pub fn with_saver_status(mut self) -> Self {
+        self.saver_status = true;
+        self
+    }
+
+    pub fn with_battery_percent(mut self) -> Self {
+        self.battery_percent = true;
+        self
+    }
+
+    // 窗口期内同一类别重复出现的相同事件只分发一次
+    pub fn debounce(mut self, window: Duration) -> Self {
+        self.debounce = Some(window);
+        self
+    }
+
+    // 每隔 interval 用 GetSystemPowerStatus 核对最近收到的 AC/DC 与节电模式通知，
+    // 连续两次与系统状态不一致 (例如会话切换后通知不再送达) 时重新注册全部观察者。
+    // 只有订阅了 AC/DC 或节电模式时才有可核对的依据
+    pub fn with_watchdog(mut self, interval: Duration) -> Self {
+        self.watchdog = Some(interval);
+        self
+    }
+
+    // 所有事件先进入同一个 mpsc 队列，由内部的一个工作线程按到达顺序逐个调用回调:
+    // 回调之间严格有序、不会并发，且始终在同一线程上执行，适合非线程安全的处理逻辑
+    pub fn with_dedicated_thread(mut self) -> Self {
+        self.dedicated_thread = true;
+        self
+    }
+
+    // 对内部每个观察者启用 with_slow_callback_warning: 回调耗时超过 threshold 时记录警告
+    pub fn with_slow_callback_warning(mut self, threshold: Duration) -> Self {
+        self.slow_callback_warning = Some(threshold);
+        self
+    }
+
+    // events_since 使用的事件日志最多保留 capacity 条 (默认 256)，写满后丢弃最早的；0 表示不记录
+    pub fn with_event_log(mut self, capacity: usize) -> Self {
+        self.event_log = capacity;
+        self
+    }
+
+    // 是否在启动时分发当前状态 (默认开启): 构造时同步查询到的值以及注册后系统立即推送的值
+    // 都会作为事件送达。关闭后 current() 照常可用，但每个类别直到值第一次真正变化才会回调
+    pub fn with_initial_snapshot(mut self, enabled: bool) -> Self {
+        self.initial_snapshot = enabled;
+        self
+    }
+
+    // 仅对 build_batched 生效: 第一个事件到达后 window 内陆续到达的事件合并为一次回调
+    pub fn with_batch_window(mut self, window: Duration) -> Self {
+        self.batch_window = Some(window);
+        self
+    }
+
+    // 与 build 相同，但回调一次收到一批事件 (按到达顺序)，适合每次回调代价较高的场景 (例如重绘界面)。
+    // 未设置 with_batch_window 时每批只有一个事件。批次在独立线程上投递
+    pub fn build_batched<F>(self, handler: F) -> Result<PowerMonitor, DeviceAwareError>
+    where F: Fn(Vec<PowerEvent>) + Send + Sync + 'static
+    {
+        let Some(window) = self.batch_window else {
+            return self.build(move |event| handler(vec![event]));
+        };
+        let (batcher, tx) = Batcher::spawn(window, handler);
+        let mut monitor = self.build(move |event| {
+            // 批处理线程已退出时忽略发送失败
+            let _ = tx.send(event);
+        })?;
+        monitor._batcher = Some(batcher);
+        Ok(monitor)
+    }
+
+    // 注册所有选中的观察者；任意一个失败则返回错误，已注册的会随之 Drop 注销
+    pub fn build<F>(self, handler: F) -> Result<PowerMonitor, DeviceAwareError>
+    where F: Fn(PowerEvent) + Send + Sync + 'static
+    {
+        // 事件日志紧挨着用户回调记录，events_since 返回的正是去抖与投递之后实际送达的事件
+        let log = Arc::new(EventHistory::with_capacity(self.event_log));
+        let handler = {
+            let log = log.clone();
+            move |event| {
+                log.push(SystemTime::now(), event);
+                handler(event);
+            }
+        };
+
+        // 专用线程模式: 用户回调改为入队，由工作线程投递
+        let (dispatcher, handler): (_, PowerEventHandler) = if self.dedicated_thread {
+            let (dispatcher, tx) = Dispatcher::spawn(handler);
+            (Some(dispatcher), Arc::new(move |event| {
+                // 工作线程已退出时忽略发送失败
+                let _ = tx.send(event);
+            }))
+        } else {
+            (None, Arc::new(handler))
+        };
+
+        // 所有观察者共享同一个用户回调
+        let handler: PowerEventHandler = match self.debounce {
+            Some(window) => {
+                // 各类别的去抖状态相互独立，避免不同类别的事件互相干扰
+                let debouncers: [Debouncer<PowerEvent>; 4] =
+                    std::array::from_fn(|_| Debouncer::new(window));
+                Arc::new(move |event| {
+                    if debouncers[event.category()].should_deliver(event) {
+                        handler(event);
+                    }
+                })
+            }
+            None => handler,
+        };
+
+        // 缓存最近一次的各项读数，供 current() 使用。
+        // 关闭启动快照时缓存照常更新，只是启动阶段未变化的值不会分发
+        let state = Arc::new(Mutex::new(PowerState::unknown()));
+        let state_changed = Arc::new(Condvar::new());
+        let startup = (!self.initial_snapshot).then(|| Arc::new(StartupFilter::default()));
+        let handler: PowerEventHandler = {
+            let startup = startup.clone();
+            let state = state.clone();
+            let state_changed = state_changed.clone();
+            Arc::new(move |event| {
+                let previous = {
+                    let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+                    let previous = *state;
+                    state.apply(event);
+                    previous
+                };
+                state_changed.notify_all();
+                if let Some(startup) = &startup
+                    && !startup.should_deliver(&previous, event)
+                {
+                    return;
+                }
+                handler(event);
+            })
+        };
+
+        // 记录观察者实际报告的值 (去抖之前)，供 watchdog 与系统状态比对
+        let seen = self.watchdog.map(|_| Arc::new(Mutex::new(LastSeen::default())));
+        let handler: PowerEventHandler = match seen.clone() {
+            Some(seen) => Arc::new(move |event| {
+                seen.lock().unwrap_or_else(|e| e.into_inner()).record(event);
+                handler(event);
+            }),
+            None => handler,
+        };
+
+        // 先同步查询再注册: 注册后系统推送的当前值总是晚于查询结果写入缓存，不会被快照覆盖
+        let snapshot = self.initial_snapshot(&state);
+        if let Some(startup) = &startup {
+            for event in &snapshot {
+                if let PowerEvent::SaverStatus(is_on) = *event {
+                    startup.seed_saver(is_on);
+                }
+            }
+        }
+
+        // 启动快照: 不必等到第一次变化，订阅者立即拿到当前值。同样在注册之前分发，
+        // 订阅者最后收到的总是系统推送的值；注册失败时已分发的快照不会撤回
+        if self.initial_snapshot {
+            for event in snapshot {
+                handler(event);
+            }
+        }
+
+        let registrations = Arc::new(Mutex::new(self.register(&handler)?));
+        let watchdog = match (self.watchdog, seen) {
+            (Some(interval), Some(seen)) => {
+                Some(self.spawn_watchdog(interval, seen, state.clone(), handler.clone(), registrations.clone()))
+            }
+            _ => None,
+        };
+
+        Ok(PowerMonitor {
+            _watchdog: watchdog,
+            _registrations: registrations,
+            _dispatcher: dispatcher,
+            _batcher: None,
+            state,
+            state_changed,
+            log,
+            additional: Mutex::new(Vec::new()),
+            shared: Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(0),
+        })
+    }
+
+    // 同步查询 AC/DC、节电模式与电量写入缓存，并返回已订阅类别对应的启动事件。
+    // 滑块位置没有同步查询接口，由注册后系统立即推送的第一次通知填入
+    fn initial_snapshot(&self, state: &Mutex<PowerState>) -> Vec<PowerEvent> {
+        let status = match system_power_status() {
+            Ok(status) => status,
+            Err(e) => {
+                log_debug!("PowerMonitor: initial status query failed: {:?}", e);
+                return Vec::new();
+            }
+        };
+
+        {
+            let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+            state.source = status.power_source();
+            state.saver_on = status.system_status_flag != 0;
+            state.battery_percent = status.battery_life_percent;
+        }
+
+        let mut events = Vec::new();
+        if self.power_source && status.power_source() != PowerSourceType::Unknown {
+            events.push(PowerEvent::PowerSource(status.power_source()));
+        }
+        if self.saver_status {
+            events.push(PowerEvent::SaverStatus(status.system_status_flag != 0));
+        }
+        if self.battery_percent
+            && let Some(percent) = status.battery_life_percent
+        {
+            events.push(PowerEvent::BatteryPercent(percent as u32));
+        }
+        events
+    }
+
+    fn spawn_watchdog(
+        self,
+        interval: Duration,
+        seen: Arc<Mutex<LastSeen>>,
+        state: Arc<Mutex<PowerState>>,
+        handler: PowerEventHandler,
+        target: Arc<Mutex<Registrations>>,
+    ) -> Watchdog {
+        let mut suspect = false;
+        Watchdog::spawn(interval, move || {
+            let status = match system_power_status() {
+                Ok(status) => status,
+                Err(e) => {
+                    log_debug!("PowerMonitor watchdog: status query failed: {:?}", e);
+                    return;
+                }
+            };
+            if !seen.lock().unwrap_or_else(|e| e.into_inner()).is_stale(&status) {
+                suspect = false;
+                return;
+            }
+            // 通知可能只是稍晚于查询到达，连续两次不一致才判定为失效
+            if !suspect {
+                suspect = true;
+                return;
+            }
+            suspect = false;
+
+            log_warn!("PowerMonitor watchdog: notifications look stale, re-registering observers");
+            // 新注册后系统会再次推送各类别的当前值。每个类别的第一次推送与缓存相同时是重复，
+            // 只记入 seen 不再分发；不同则说明失效期间错过了变化，照常分发
+            let pushed: Arc<[AtomicBool; 4]> = Arc::default();
+            let fresh: PowerEventHandler = {
+                let seen = seen.clone();
+                let state = state.clone();
+                let handler = handler.clone();
+                Arc::new(move |event| {
+                    let initial = !pushed[event.category()].swap(true, Ordering::AcqRel);
+                    if initial && state.lock().unwrap_or_else(|e| e.into_inner()).reflects(event) {
+                        seen.lock().unwrap_or_else(|e| e.into_inner()).record(event);
+                        return;
+                    }
+                    handler(event);
+                })
+            };
+            // 先注册新的再替换，失败时保留原有注册，下一轮再试
+            match self.register(&fresh) {
+                Ok(fresh) => *target.lock().unwrap_or_else(|e| e.into_inner()) = fresh,
+                Err(e) => log_error!("PowerMonitor watchdog: re-registration failed: {:?}", e),
+            }
+        })
+    }
+
+    fn register(&self, handler: &PowerEventHandler) -> Result<Registrations, DeviceAwareError> {
+        let mut effective_mode = if self.effective_mode {
+            let h = handler.clone();
+            Some(EffectiveModeObserver::new(move |mode| {
+                h(PowerEvent::EffectiveMode(mode));
+            })?)
+        } else {
+            None
+        };
+
+        let mut settings = Vec::new();
+
+        if self.power_source {
+            let h = handler.clone();
+            settings.push(PowerSettingObserver::new(GUID_ACDC_POWER_SOURCE, move |val| {
+                h(PowerEvent::PowerSource(PowerSourceType::from(val)));
+            })?);
+        }
+
+        if self.saver_status {
+            let h = handler.clone();
+            settings.push(PowerSettingObserver::new(GUID_POWER_SAVING_STATUS, move |val| {
+                h(PowerEvent::SaverStatus(val != 0));
+            })?);
+        }
+
+        if self.battery_percent {
+            let h = handler.clone();
+            settings.push(PowerSettingObserver::new(GUID_BATTERY_PERCENTAGE_REMAINING, move |val| {
+                h(PowerEvent::BatteryPercent(val));
+            })?);
+        }
+
+        if let Some(threshold) = self.slow_callback_warning {
+            effective_mode = effective_mode.map(|observer| observer.with_slow_callback_warning(threshold));
+            settings = settings
+                .into_iter()
+                .map(|observer| observer.with_slow_callback_warning(threshold))
+                .collect();
+        }
+
+        Ok(Registrations {
+            _effective_mode: effective_mode,
+            _settings: settings,
+        })
+    }
+}
+
+// watchdog 用: 最近一次收到的 AC/DC 与节电模式通知
+#[derive(Default)]
+struct LastSeen {
+    source: Option<PowerSourceType>,
+    saver_on: Option<bool>,
+}
+
+impl LastSeen {
+    fn record(&mut self, event: PowerEvent) {
+        match event {
+            PowerEvent::PowerSource(source) => self.source = Some(source),
+            PowerEvent::SaverStatus(is_on) => self.saver_on = Some(is_on),
+            _ => {}
+        }
+    }
+
+    // GetSystemPowerStatus 无法区分短期/UPS 供电，这里只比较 "是否电池供电"
+    fn is_stale(&self, status: &SystemPowerStatus) -> bool {
+        let actual = status.power_source();
+        let source_stale = actual != PowerSourceType::Unknown
+            && self.source.is_some_and(|seen| {
+                seen.is_battery() != actual.is_battery()
+            });
+        let saver_stale = self.saver_on.is_some_and(|seen| seen != (status.system_status_flag != 0));
+        source_stale || saver_stale
+    }
+}
+
+// ============================================================================
+// MultiSettingObserver: 一次订阅多个 GUID，统一回调 (GUID, 值)，适合记录日志
+// ============================================================================
+
+pub struct MultiSettingObserver {
+    // 仅用于保持注册存活，Drop 时自动注销
+    observers: Vec<PowerSettingObserver>,
+}
+
+impl MultiSettingObserver {
+    // 任意一个 GUID 注册失败则返回错误，已注册的会随之 Drop 注销
+    pub fn new<F>(guids: &[GUID], handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(GUID, u32) + Send + Sync + 'static
+    {
+        let handler = Arc::new(handler);
+        let observers = guids
+            .iter()
+            .map(|&guid| {
+                let h = handler.clone();
+                PowerSettingObserver::new(guid, move |val| h(guid, val))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(MultiSettingObserver { observers })
+    }
+
+    // 已订阅的 GUID
+    pub fn guids(&self) -> impl Iterator<Item = GUID> + '_ {
+        self.observers.iter().map(PowerSettingObserver::guid)
+    }
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use std::sync::mpsc;
+
+    use super::*;
+    use crate::power_observer::lock_global_state;
+    use crate::status::{set_system_power_status, SystemPowerStatus};
+
+    fn set_status(ac_online: bool, saver_on: bool) {
+        set_system_power_status(Some(SystemPowerStatus {
+            ac_line_status: u8::from(ac_online),
+            battery_flag: 0,
+            battery_life_percent: None,
+            battery_life_time: None,
+            battery_full_life_time: None,
+            system_status_flag: u8::from(saver_on),
+        }));
+    }
+
+    // 关闭启动快照时，第一次推送的节电状态 (即使是 "开启") 只作为基准，真正变化后才分发
+    #[test]
+    fn startup_filter_treats_first_saver_value_as_baseline() {
+        let _serial = lock_global_state();
+        let (tx, rx) = mpsc::channel();
+        let monitor = PowerMonitorBuilder::new()
+            .with_saver_status()
+            .with_initial_snapshot(false)
+            .build(move |event| {
+                let _ = tx.send(event);
+            })
+            .unwrap();
+
+        PowerSettingObserver::inject_all(GUID_POWER_SAVING_STATUS, 1);
+        PowerSettingObserver::inject_all(GUID_POWER_SAVING_STATUS, 1);
+        assert!(rx.try_recv().is_err());
+        assert!(monitor.current().saver_on);
+
+        PowerSettingObserver::inject_all(GUID_POWER_SAVING_STATUS, 0);
+        assert_eq!(rx.try_recv(), Ok(PowerEvent::SaverStatus(false)));
+    }
+
+    // 第一次推送的滑块位置只作为基准；即使是 Unknown(u8::MAX) 也是真实读数，之后的变化照常分发
+    #[test]
+    fn startup_filter_treats_first_mode_as_baseline() {
+        let _serial = lock_global_state();
+        EffectiveModeObserver::inject_all(EffectivePowerMode::Unknown(u8::MAX));
+        let (tx, rx) = mpsc::channel();
+        let monitor = PowerMonitorBuilder::new()
+            .with_effective_mode()
+            .with_initial_snapshot(false)
+            .build(move |event| {
+                let _ = tx.send(event);
+            })
+            .unwrap();
+        assert!(rx.try_recv().is_err());
+        assert_eq!(monitor.current().effective_mode, Some(EffectivePowerMode::Unknown(u8::MAX)));
+
+        EffectiveModeObserver::inject_all(EffectivePowerMode::Balanced);
+        assert_eq!(rx.try_recv(), Ok(PowerEvent::EffectiveMode(EffectivePowerMode::Balanced)));
+    }
+
+    // 启动快照在注册之前写入并分发，注册时系统推送的 (更新的) 值随后到达，不会被快照覆盖
+    #[test]
+    fn snapshot_precedes_values_pushed_on_registration() {
+        let _serial = lock_global_state();
+        set_status(true, false);
+        PowerSettingObserver::inject_all(GUID_POWER_SAVING_STATUS, 1);
+        let (tx, rx) = mpsc::channel();
+        let monitor = PowerMonitorBuilder::new()
+            .with_saver_status()
+            .build(move |event| {
+                let _ = tx.send(event);
+            })
+            .unwrap();
+
+        assert_eq!(rx.try_recv(), Ok(PowerEvent::SaverStatus(false)));
+        assert_eq!(rx.try_recv(), Ok(PowerEvent::SaverStatus(true)));
+        assert!(rx.try_recv().is_err());
+        assert!(monitor.current().saver_on);
+    }
+
+    // 事件日志在去抖之后记录: 被去抖丢弃的重复值不会出现在 events_since 中
+    #[test]
+    fn event_log_records_only_delivered_events() {
+        let _serial = lock_global_state();
+        let (tx, rx) = mpsc::channel();
+        let monitor = PowerMonitorBuilder::new()
+            .with_saver_status()
+            .debounce(Duration::from_secs(60))
+            .build(move |event| {
+                let _ = tx.send(event);
+            })
+            .unwrap();
+
+        for _ in 0..3 {
+            PowerSettingObserver::inject_all(GUID_POWER_SAVING_STATUS, 1);
+        }
+        assert_eq!(rx.try_iter().collect::<Vec<_>>(), [PowerEvent::SaverStatus(true)]);
+        let logged: Vec<_> = monitor.events_since(SystemTime::UNIX_EPOCH).into_iter().map(|(_, event)| event).collect();
+        assert_eq!(logged, [PowerEvent::SaverStatus(true)]);
+    }
+
+    // watchdog 重新注册后系统再次推送的当前值与缓存相同，不会作为重复事件分发
+    #[test]
+    fn watchdog_reregistration_does_not_redeliver_current_values() {
+        let _serial = lock_global_state();
+        set_status(true, false);
+        PowerSettingObserver::inject_all(GUID_POWER_SAVING_STATUS, 0);
+        let (tx, rx) = mpsc::channel();
+        let monitor = PowerMonitorBuilder::new()
+            .with_saver_status()
+            .with_watchdog(Duration::from_millis(5))
+            .build(move |event| {
+                let _ = tx.send(event);
+            })
+            .unwrap();
+        while rx.try_recv().is_ok() {}
+
+        // 系统状态与收到的通知不一致，watchdog 反复重新注册；每次注册都会推送缓存中的 false
+        set_status(true, true);
+        std::thread::sleep(Duration::from_millis(100));
+        assert!(rx.try_recv().is_err());
+        assert!(!monitor.current().saver_on);
+        drop(monitor);
+    }
+
+    // 之后加入的订阅者立即收到最近一次的值，已有订阅者不会重复收到
+    #[test]
+    fn late_subscribers_receive_the_cached_value() {
+        let guid = GUID::from_u128(0x6d3b7a10_47a1_4c55_9e2c_1f0a5b6c0301);
+        let _serial = lock_global_state();
+        let monitor = PowerMonitorBuilder::new().build(|_| {}).unwrap();
+
+        let (first_tx, first_rx) = mpsc::channel();
+        monitor
+            .subscribe(guid, move |val| {
+                let _ = first_tx.send(val);
+            })
+            .unwrap();
+        // 还没有任何通知，第一个订阅者只等系统推送
+        assert!(first_rx.try_recv().is_err());
+        PowerSettingObserver::inject_all(guid, 7);
+        assert_eq!(first_rx.try_recv(), Ok(7));
+
+        let (second_tx, second_rx) = mpsc::channel();
+        let second = monitor
+            .subscribe(guid, move |val| {
+                let _ = second_tx.send(val);
+            })
+            .unwrap();
+        assert_eq!(second_rx.try_recv(), Ok(7));
+        assert!(first_rx.try_recv().is_err());
+
+        PowerSettingObserver::inject_all(guid, 8);
+        assert_eq!(first_rx.try_recv(), Ok(8));
+        assert_eq!(second_rx.try_recv(), Ok(8));
+        assert!(monitor.unsubscribe(second));
+    }
+}
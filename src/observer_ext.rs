@@ -0,0 +1,298 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use windows_core::GUID;
+
+use crate::{
+    DeviceAwareError, EffectiveModeChange, EffectiveModeObserver, EffectivePowerMode, PowerSettingObserver,
+};
+
+// ============================================================================
+// 去抖: 窗口期内与上一次送达值相同的通知直接丢弃
+// ============================================================================
+
+pub(crate) struct Debouncer<T> {
+    window: Duration,
+    last: Mutex<Option<(T, Instant)>>,
+}
+
+impl<T: PartialEq> Debouncer<T> {
+    pub(crate) fn new(window: Duration) -> Self {
+        Self { window, last: Mutex::new(None) }
+    }
+
+    // 返回 true 表示应当送达，并记录本次的值与时间
+    pub(crate) fn should_deliver(&self, val: T) -> bool {
+        let now = Instant::now();
+        let mut last = self.last.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((prev, at)) = last.as_ref()
+            && *prev == val
+            && now.duration_since(*at) < self.window
+        {
+            return false;
+        }
+        *last = Some((val, now));
+        true
+    }
+}
+
+// ============================================================================
+// 基于 new() 组合出的便捷构造函数，与具体后端 (Win32 / mock) 无关
+// ============================================================================
+
+impl EffectiveModeObserver {
+    // 多个观察者共用同一个回调: 每个观察者只持有 Arc 的一份克隆，闭包本身只分配一次
+    pub fn new_shared(handler: Arc<dyn Fn(EffectivePowerMode) + Send + Sync>) -> Result<Self, DeviceAwareError> {
+        Self::new(move |mode| handler(mode))
+    }
+
+    // 回调同时收到上一次的值；第一次通知时 previous 为 None
+    pub fn new_with_prev<F>(handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(Option<EffectivePowerMode>, EffectivePowerMode) + Send + Sync + 'static
+    {
+        let last = Mutex::new(None);
+        Self::new(move |mode| {
+            let previous = last.lock().unwrap_or_else(|e| e.into_inner()).replace(mode);
+            handler(previous, mode);
+        })
+    }
+
+    // 接受 FnMut 的有状态回调: 闭包放在 Mutex 中，每次通知时加锁调用。
+    // 系统不会在同一线程上重入回调，但不同通知可能来自不同线程，此时会互相等待；
+    // 回调内部不要再同步等待同一个观察者的下一次通知，否则会死锁
+    pub fn new_mut<F>(handler: F) -> Result<Self, DeviceAwareError>
+    where F: FnMut(EffectivePowerMode) + Send + 'static
+    {
+        let handler = Mutex::new(handler);
+        Self::new(move |mode| {
+            let mut handler = handler.lock().unwrap_or_else(|e| e.into_inner());
+            handler(mode);
+        })
+    }
+
+    // 只在模式真正变化时回调，并同时给出变化前后的值；注册后的首次通知只用于建立基准
+    pub fn new_with_change<F>(handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(EffectiveModeChange) + Send + Sync + 'static
+    {
+        Self::new_with_prev(move |previous, to| {
+            if let Some(from) = previous
+                && from != to
+            {
+                handler(EffectiveModeChange { from, to });
+            }
+        })
+    }
+
+    // window 内重复出现的相同模式只回调一次
+    pub fn new_debounced<F>(window: Duration, handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(EffectivePowerMode) + Send + Sync + 'static
+    {
+        let debouncer = Debouncer::new(window);
+        Self::new(move |mode| {
+            if debouncer.should_deliver(mode) {
+                handler(mode);
+            }
+        })
+    }
+}
+
+impl PowerSettingObserver {
+    // 多个 GUID 共用同一个回调，见 EffectiveModeObserver::new_shared
+    pub fn new_shared(guid: GUID, handler: Arc<dyn Fn(u32) + Send + Sync>) -> Result<Self, DeviceAwareError> {
+        Self::new(guid, move |val| handler(val))
+    }
+
+    // 回调同时收到上一次的值；第一次通知时 previous 为 None
+    pub fn new_with_prev<F>(guid: GUID, handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(Option<u32>, u32) + Send + Sync + 'static
+    {
+        let last = Mutex::new(None);
+        Self::new(guid, move |val| {
+            let previous = last.lock().unwrap_or_else(|e| e.into_inner()).replace(val);
+            handler(previous, val);
+        })
+    }
+
+    // 接受 FnMut 的有状态回调，加锁与重入注意事项同 EffectiveModeObserver::new_mut
+    pub fn new_mut<F>(guid: GUID, handler: F) -> Result<Self, DeviceAwareError>
+    where F: FnMut(u32) + Send + 'static
+    {
+        let handler = Mutex::new(handler);
+        Self::new(guid, move |val| {
+            let mut handler = handler.lock().unwrap_or_else(|e| e.into_inner());
+            handler(val);
+        })
+    }
+
+    // 使用自定义解码器订阅任意载荷 (GUID、结构体、字节数组等)；
+    // decode 返回 None 表示载荷无法解码，本次通知不会调用 handler
+    pub fn new_decoded<T, D, F>(guid: GUID, decode: D, handler: F) -> Result<Self, DeviceAwareError>
+    where
+        D: Fn(&[u8]) -> Option<T> + Send + Sync + 'static,
+        F: Fn(T) + Send + Sync + 'static,
+    {
+        Self::new_raw(guid, move |data| match decode(data) {
+            Some(val) => handler(val),
+            None => log_debug!("PowerSettingObserver: failed to decode {}-byte payload for GUID {:?}", data.len(), guid),
+        })
+    }
+
+    // 调试用: 回调同时收到解码后的 u32 与 POWERBROADCAST_SETTING.Data 的原始字节副本。
+    // 只有 4 字节载荷会回调；需要观察其他长度的载荷请使用 new_raw
+    pub fn new_with_raw<F>(guid: GUID, handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(u32, Vec<u8>) + Send + Sync + 'static
+    {
+        Self::new_decoded(guid, |data| decode_u32(data).map(|val| (val, data.to_vec())), move |(val, raw)| {
+            handler(val, raw)
+        })
+    }
+
+    // window 内重复出现的相同取值只回调一次 (例如插拔电源时连续多次的 AC/DC 通知)
+    pub fn new_debounced<F>(guid: GUID, window: Duration, handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(u32) + Send + Sync + 'static
+    {
+        let debouncer = Debouncer::new(window);
+        Self::new(guid, move |val| {
+            if debouncer.should_deliver(val) {
+                handler(val);
+            }
+        })
+    }
+}
+
+// ============================================================================
+// map 组合子: 在构造时指定 u32 到目标类型的转换，回调直接收到转换后的值。
+// 转换与回调一样在系统回调线程上执行
+//   PowerSettingObserver::new_mapped(GUID_ACDC_POWER_SOURCE, PowerSourceType::from, |source| ...)
+//   PowerSettingObserver::mapped(GUID_ACDC_POWER_SOURCE, PowerSourceType::from)
+//       .map(|source| source.is_battery())
+//       .subscribe(|on_battery| ...)
+// 转换必须在注册之前确定: new 返回 Result，其后的 .map 会是 Result::map，
+// 而且注册完成时回调的参数类型已经固定为 u32
+// ============================================================================
+
+pub struct MappedPowerSetting<M> {
+    guid: GUID,
+    map: M,
+}
+
+impl PowerSettingObserver {
+    // 单步转换的简写，等同于 mapped(guid, map).subscribe(handler)
+    pub fn new_mapped<U, M, F>(guid: GUID, map: M, handler: F) -> Result<Self, DeviceAwareError>
+    where
+        M: Fn(u32) -> U + Send + Sync + 'static,
+        F: Fn(U) + Send + Sync + 'static,
+    {
+        Self::mapped(guid, map).subscribe(handler)
+    }
+
+    // 需要串联多步转换时使用，最后以 subscribe 注册
+    pub fn mapped<U, M>(guid: GUID, map: M) -> MappedPowerSetting<M>
+    where M: Fn(u32) -> U + Send + Sync + 'static
+    {
+        MappedPowerSetting { guid, map }
+    }
+}
+
+impl<M> MappedPowerSetting<M> {
+    // 在已有转换之后追加一步转换
+    pub fn map<U, V, N>(self, next: N) -> MappedPowerSetting<impl Fn(u32) -> V + Send + Sync + 'static>
+    where
+        M: Fn(u32) -> U + Send + Sync + 'static,
+        N: Fn(U) -> V + Send + Sync + 'static,
+    {
+        let map = self.map;
+        MappedPowerSetting { guid: self.guid, map: move |val| next(map(val)) }
+    }
+
+    // 注册通知；之后的用法与普通 PowerSettingObserver 相同
+    pub fn subscribe<U, F>(self, handler: F) -> Result<PowerSettingObserver, DeviceAwareError>
+    where
+        M: Fn(u32) -> U + Send + Sync + 'static,
+        F: Fn(U) + Send + Sync + 'static,
+    {
+        let map = self.map;
+        PowerSettingObserver::new(self.guid, move |val| handler(map(val)))
+    }
+}
+
+// new_decoded 使用的内置解码器: 恰好 4 字节时按本机字节序解码为 u32
+pub fn decode_u32(data: &[u8]) -> Option<u32> {
+    data.try_into().ok().map(u32::from_ne_bytes)
+}
+
+// new / new_timestamped 的 u32 便捷路径，Win32 与 mock 后端共用: 只有 4 字节载荷才解码，
+// 空载荷与长度不符的载荷记录异常后丢弃，不猜测取值 (例如当作 0)
+#[cfg(any(windows, feature = "mock"))]
+pub(crate) fn decode_notification(guid: GUID, data: &[u8]) -> Option<u32> {
+    if data.is_empty() {
+        // 部分 GUID (如 GUID_BATTERY_PERCENTAGE_REMAINING) 在注册后的首次通知可能不带数据，
+        // 此时无值可解码，记录一下而不是悄悄丢弃
+        log_warn!("PowerSettingObserver: received empty payload for GUID {:?}", guid);
+        return None;
+    }
+    let val = decode_u32(data);
+    if val.is_none() {
+        log_warn!(
+            "PowerSettingObserver: {}-byte payload for GUID {:?} is not a u32, dropped (use new_raw or new_decoded)",
+            data.len(),
+            guid
+        );
+    }
+    val
+}
+
+// new_decoded 使用的内置解码器: 恰好 16 字节时按 GUID 的内存布局解码
+pub fn decode_guid(data: &[u8]) -> Option<GUID> {
+    let bytes: [u8; 16] = data.try_into().ok()?;
+    Some(GUID::from_values(
+        u32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+        u16::from_ne_bytes([bytes[4], bytes[5]]),
+        u16::from_ne_bytes([bytes[6], bytes[7]]),
+        [bytes[8], bytes[9], bytes[10], bytes[11], bytes[12], bytes[13], bytes[14], bytes[15]],
+    ))
+}
+
+// 逐个尝试注册再立即注销，用于探测当前系统 / 硬件支持哪些电源设置 GUID
+// (例如台式机上可能没有盖子开关或电池相关的设置)
+pub fn probe_guids(guids: &[GUID]) -> Vec<(GUID, Result<(), DeviceAwareError>)> {
+    guids
+        .iter()
+        .map(|&guid| {
+            // 观察者在闭包结束时 Drop，随即注销
+            let result = PowerSettingObserver::new_raw(guid, |_| {}).map(|_| ());
+            (guid, result)
+        })
+        .collect()
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use std::sync::mpsc;
+
+    use super::*;
+    use crate::PowerSourceType;
+
+    #[test]
+    fn mapped_observers_receive_converted_values() {
+        let guid = GUID::from_u128(0x6d3b7a10_47a1_4c55_9e2c_1f0a5b6c0201);
+        let (tx, rx) = mpsc::channel();
+        let single = PowerSettingObserver::new_mapped(guid, PowerSourceType::from, move |source| {
+            let _ = tx.send(source);
+        })
+        .unwrap();
+
+        let (tx, chained_rx) = mpsc::channel();
+        let chained = PowerSettingObserver::mapped(guid, PowerSourceType::from)
+            .map(PowerSourceType::is_battery)
+            .subscribe(move |on_battery| {
+                let _ = tx.send(on_battery);
+            })
+            .unwrap();
+
+        PowerSettingObserver::inject_all(guid, 1);
+        assert_eq!(rx.try_recv(), Ok(PowerSourceType::Battery));
+        assert_eq!(chained_rx.try_recv(), Ok(true));
+        drop((single, chained));
+    }
+}
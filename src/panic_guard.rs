@@ -0,0 +1,14 @@
+use std::panic::{self, AssertUnwindSafe};
+
+// 用户回调运行在系统线程上，panic 不能越过 Win32 FFI 边界展开 (未定义行为)。
+// 这里捕获 panic、记录日志，然后正常返回给系统。
+pub(crate) fn guard_callback<F: FnOnce()>(source: &str, f: F) {
+    if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(f)) {
+        let msg = payload
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("<non-string panic payload>");
+        log_error!("{}: user callback panicked: {}", source, msg);
+    }
+}
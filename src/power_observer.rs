@@ -0,0 +1,622 @@
+use std::ffi::c_void;
+use std::fmt;
+use std::sync::mpsc::{self, Receiver};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use windows_core::{Error, GUID};
+use windows::Win32::Foundation::{HANDLE, HWND};
+use windows::Win32::System::Power::{
+    PowerRegisterForEffectivePowerModeNotifications, PowerUnregisterFromEffectivePowerModeNotifications,
+    RegisterPowerSettingNotification, UnregisterPowerSettingNotification,
+    EFFECTIVE_POWER_MODE, EFFECTIVE_POWER_MODE_V1, EFFECTIVE_POWER_MODE_V2,
+    HPOWERNOTIFY, DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS,
+    POWERBROADCAST_SETTING, GetSystemPowerStatus, SYSTEM_POWER_STATUS,
+};
+use windows::Win32::System::Services::SERVICE_STATUS_HANDLE;
+use windows::Win32::UI::WindowsAndMessaging::{
+    DEVICE_NOTIFY_CALLBACK, DEVICE_NOTIFY_SERVICE_HANDLE, DEVICE_NOTIFY_WINDOW_HANDLE,
+    PBT_POWERSETTINGCHANGE, REGISTER_NOTIFICATION_FLAGS,
+};
+
+use crate::change_signal::{ArrivalSignal, ChangeSignal};
+use crate::context::OwnedContext;
+use crate::history::EventHistory;
+use crate::observer_ext::decode_notification;
+use crate::panic_guard::guard_callback;
+use crate::reentrancy::{defer_release, enter_callback, is_in_callback, release_when_idle};
+use crate::slow_callback::SlowCallbackWarning;
+use crate::guids::{GUID_ACDC_POWER_SOURCE, GUID_POWER_SAVING_STATUS};
+use crate::{DeviceAwareError, EffectivePowerMode};
+
+// ============================================================================
+// 1. EffectiveModeObserver (修复版)
+// ============================================================================
+
+// 定义回调类型别名，方便处理
+pub type EffectiveModeCallback = Box<dyn Fn(EffectivePowerMode) + Send + Sync>;
+
+// 实际保存在堆上的回调，额外携带系统调用 static_cb 时记录的时间戳
+type EffectiveModeContext = Box<dyn Fn(EffectivePowerMode, SystemTime) + Send + Sync>;
+
+// 内存所有权约定 (分配与释放由 OwnedContext 负责):
+// - new_timestamped 中只分配一次上下文；V2 失败后重试 V1 复用同一个上下文，
+//   两次都失败才回滚，因此不会重复释放
+// - 注册成功后只在 unsubscribe 中注销之后释放一次，随即把 handle 置空、上下文变为空，
+//   之后的 unsubscribe / Drop 都是空操作
+//
+// 多个实例并存:
+// - PowerRegisterForEffectivePowerModeNotifications 是进程级的注册接口，但每次调用都得到独立的
+//   注册句柄，系统把各自注册时传入的 Context 原样交回，互不影响；同一进程创建多个观察者是安全的，
+//   每个都会收到全部通知 (包括注册后立即推送的当前模式)
+// - 所有实例共用同一个 static_cb 函数指针，但它不持有任何状态，只解引用系统交回的 Context，
+//   因此不存在跨实例的共享状态或别名；每个实例 Drop 时只注销并释放自己的 handle / 上下文
+// - 只需要一份滑块状态时，优先让多个消费者共享一个观察者 (或使用 PowerMonitor)，
+//   以免重复注册带来的额外系统回调
+pub struct EffectiveModeObserver {
+    handle: *mut c_void,
+    // 交给系统的回调上下文，注销之后释放
+    context: OwnedContext<EffectiveModeContext>,
+    // 实际注册成功的接口版本 (EFFECTIVE_POWER_MODE_V2 或 V1)
+    version: u32,
+    slow: Arc<SlowCallbackWarning>,
+}
+
+impl EffectiveModeObserver {
+    pub fn new<F>(handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(EffectivePowerMode) + Send + Sync + 'static 
+    {
+        Self::new_timestamped(move |mode, _at| handler(mode))
+    }
+
+    // 回调额外收到事件到达时间 (在系统线程进入 static_cb 时记录，而不是用户代码执行时)
+    pub fn new_timestamped<F>(handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(EffectivePowerMode, SystemTime) + Send + Sync + 'static 
+    {
+        // 1. 创建闭包的胖指针 Box<dyn Fn>，并在其中为用户回调计时
+        let slow = Arc::new(SlowCallbackWarning::default());
+        let timer = slow.clone();
+        let callback: EffectiveModeContext = Box::new(move |mode, at| {
+            timer.time("EffectiveModeObserver", || handler(mode, at));
+        });
+        
+        // 2. 将胖指针装入外层 Box (Double Boxing)，得到指向 "Box<dyn Fn>" 的瘦指针，适合传给 void*；
+        // 两个版本都注册失败时 OwnedContext 负责回滚
+        let registered = OwnedContext::register_with(callback, |context| {
+            let mut handle = std::ptr::null_mut();
+            // V2 仅在 Windows 11 21H2+ 可用，旧系统上退回 V1
+            let mut version = EFFECTIVE_POWER_MODE_V2;
+            let mut result = Self::register(version, context, &mut handle);
+            if let Err(e) = &result {
+                log_debug!("EFFECTIVE_POWER_MODE_V2 registration failed ({:?}), retrying with V1", e);
+                version = EFFECTIVE_POWER_MODE_V1;
+                result = Self::register(version, context, &mut handle);
+            }
+            result.map(|()| (handle, version))
+        });
+
+        let (context, (handle, version)) = match registered {
+            Ok(registered) => registered,
+            Err(e) => {
+                log_error!("PowerRegisterForEffectivePowerModeNotifications failed: {:?}", e);
+                return Err(DeviceAwareError::Registration {
+                    guid: None,
+                    api: "PowerRegisterForEffectivePowerModeNotifications",
+                    error: e,
+                });
+            }
+        };
+
+        Ok(EffectiveModeObserver {
+            handle,
+            context,
+            version,
+            slow,
+        })
+    }
+
+    fn register(version: u32, context: *const c_void, handle: &mut *mut c_void) -> Result<(), Error> {
+        unsafe {
+            PowerRegisterForEffectivePowerModeNotifications(
+                version,
+                Some(Self::static_cb),
+                Some(context), // 传入瘦指针
+                handle,
+            )
+        }
+    }
+
+    // 与系统协商成功的接口版本: EFFECTIVE_POWER_MODE_V2 或 EFFECTIVE_POWER_MODE_V1
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    // 用户回调耗时超过 threshold 时记录一条警告，用于排查在通知线程上做阻塞操作的回调
+    pub fn with_slow_callback_warning(self, threshold: Duration) -> Self {
+        self.slow.set_threshold(threshold);
+        self
+    }
+
+    /// PowerRegisterForEffectivePowerModeNotifications 返回的注册句柄，供与其他 Win32 / WinRT 代码互操作；
+    /// unsubscribe 之后为空句柄
+    ///
+    /// # Safety
+    /// 句柄仍归观察者所有: 不要自行调用 PowerUnregisterFromEffectivePowerModeNotifications，
+    /// 否则观察者 Drop 时会重复注销并释放仍可能被系统回调使用的上下文；也不要在观察者 Drop 之后继续使用
+    pub unsafe fn raw_handle(&self) -> HANDLE {
+        HANDLE(self.handle)
+    }
+
+    // 基于 channel 的订阅：事件推送到 Receiver，由调用方在自己的线程里 recv()
+    pub fn new_channel() -> Result<(Self, Receiver<EffectivePowerMode>), DeviceAwareError> {
+        let (tx, rx) = mpsc::channel();
+        let observer = Self::new(move |mode| {
+            // 接收端已被丢弃时忽略发送失败
+            let _ = tx.send(mode);
+        })?;
+        Ok((observer, rx))
+    }
+
+    // 主动注销通知并释放回调；之后 Drop 不再做任何事，重复调用也是安全的
+    // 可以在自己的回调中调用 (例如只处理第一次通知)，此时注销与释放推迟到回调返回之后
+    pub fn unsubscribe(&mut self) {
+        if is_in_callback(self.context.as_ptr()) {
+            // 句柄不是 Send，以整数形式交给释放线程
+            let handle = std::mem::replace(&mut self.handle, std::ptr::null_mut()) as usize;
+            let context = self.context.take();
+            defer_release("EffectiveModeObserver", context, move || {
+                unsafe {
+                    let _ = PowerUnregisterFromEffectivePowerModeNotifications(handle as *mut c_void);
+                }
+            });
+            return;
+        }
+        if !self.handle.is_null() {
+            log_debug!("EffectiveModeObserver: unregistering effective power mode notifications");
+            unsafe {
+                let _ = PowerUnregisterFromEffectivePowerModeNotifications(self.handle);
+            }
+            self.handle = std::ptr::null_mut();
+        }
+        // 5. 注销之后等仍在执行的回调返回再释放上下文；随即置空，Drop 或再次 unsubscribe 都是空操作
+        release_when_idle(&mut self.context);
+    }
+
+    unsafe extern "system" fn static_cb(mode: EFFECTIVE_POWER_MODE, context: *const c_void) {
+        let at = SystemTime::now();
+        if !context.is_null() {
+            // 3. 将 void* 还原为 &Box<dyn Fn>，再调用；上下文在注销之前一直有效
+            let callback = unsafe { OwnedContext::<EffectiveModeContext>::get(context) };
+            log_trace!("EffectiveModeObserver: mode {:?}", mode);
+            enter_callback(context, || {
+                guard_callback("EffectiveModeObserver", || callback(EffectivePowerMode::from(mode), at));
+            });
+        }
+    }
+}
+
+// SAFETY: 上下文中的回调本身是 Send + Sync 的；它只会被系统回调线程以只读方式解引用，
+// 并且只在 unsubscribe (需要 &mut self) 或 Drop 中释放一次。
+// handle 只是交给系统注销用的不透明句柄，不会在 Rust 侧被解引用。
+unsafe impl Send for EffectiveModeObserver {}
+// SAFETY: 所有 &self 方法都不会触碰上下文；raw_handle 只复制句柄值
+unsafe impl Sync for EffectiveModeObserver {}
+
+impl Drop for EffectiveModeObserver {
+    fn drop(&mut self) {
+        self.unsubscribe();
+    }
+}
+
+// 只打印注册状态，不包含回调本身
+impl fmt::Debug for EffectiveModeObserver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EffectiveModeObserver")
+            .field("active", &!self.handle.is_null())
+            .field("version", &self.version)
+            .finish()
+    }
+}
+
+// ============================================================================
+// 2. PowerSettingObserver (修复版)
+// ============================================================================
+
+// 回调收到的 u32 含义取决于订阅的 GUID:
+// - GUID_ACDC_POWER_SOURCE: 0 = AC, 1 = 电池, 2 = 短期/UPS
+// - GUID_POWER_SAVING_STATUS: 0 = 关闭, 1 = 开启
+// - GUID_BATTERY_PERCENTAGE_REMAINING: 剩余电量百分比, 取值 [0, 100]
+pub type PowerSettingCallback = Box<dyn Fn(u32) + Send + Sync>;
+
+// 底层回调: 直接收到 POWERBROADCAST_SETTING.Data 的完整载荷 (长度为 DataLength)
+pub type RawPowerSettingCallback = Box<dyn Fn(&[u8]) + Send + Sync>;
+
+// 实际保存在堆上的回调，额外携带系统调用 static_callback 时记录的时间戳
+type PowerSettingContext = Box<dyn Fn(&[u8], SystemTime) + Send + Sync>;
+
+// 三种注册目标 (RegisterPowerSettingNotification 的 hRecipient + Flags):
+// - DEVICE_NOTIFY_CALLBACK (new / new_timestamped / new_raw): hRecipient 指向
+//   DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS，系统在线程池线程上调用 static_callback
+// - DEVICE_NOTIFY_WINDOW_HANDLE (new_for_hwnd): hRecipient 是窗口句柄，通知经 WM_POWERBROADCAST 投递
+// - DEVICE_NOTIFY_SERVICE_HANDLE (new_for_service_handle): hRecipient 是服务状态句柄，
+//   通知经 SERVICE_CONTROL_POWEREVENT 投递到 HandlerEx
+// 后两种没有回调上下文，context 为空
+//
+// 内存所有权约定 (同一 GUID 注册多次也成立，分配与释放由 OwnedContext 负责):
+// - 每个观察者在 register 中各自分配一次上下文，互不共享
+// - 上下文只会被释放一次: 注册失败时立即回滚，或在 unsubscribe 中注销之后释放
+// - unsubscribe 释放后把 handle 置为 None、上下文变为空，因此之后的 unsubscribe / Drop 都是空操作
+pub struct PowerSettingObserver {
+    guid: GUID,
+    handle: Option<HPOWERNOTIFY>,
+    context: OwnedContext<PowerSettingContext>,
+    change: Arc<ChangeSignal>,
+    arrived: Arc<ArrivalSignal>,
+    history: Arc<EventHistory>,
+    paused: Arc<AtomicBool>,
+    slow: Arc<SlowCallbackWarning>,
+}
+
+impl PowerSettingObserver {
+    pub fn new<F>(guid: GUID, handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(u32) + Send + Sync + 'static
+    {
+        Self::new_timestamped(guid, move |val, _at| handler(val))
+    }
+
+    // 回调额外收到事件到达时间 (在系统线程进入 static_callback 时记录，而不是用户代码执行时)
+    pub fn new_timestamped<F>(guid: GUID, handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(u32, SystemTime) + Send + Sync + 'static
+    {
+        let change = Arc::new(ChangeSignal::default());
+        let signal = change.clone();
+        let history = Arc::new(EventHistory::default());
+        let events = history.clone();
+
+        // u32 便捷路径: 在原始载荷之上解码，只有 4 字节载荷才会调用 handler
+        Self::register(guid, change, history, Box::new(move |data: &[u8], at| {
+            // POWERBROADCAST_SETTING 的 DWORD 载荷按本机字节序写入，所有受支持的目标都是小端
+            if let Some(val) = decode_notification(guid, data) {
+                events.push(at, val);
+                handler(val, at);
+                signal.notify(val);
+            }
+        }))
+    }
+
+    // 订阅载荷不是单个 u32 的 GUID，回调收到完整的原始字节
+    pub fn new_raw<F>(guid: GUID, handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(&[u8]) + Send + Sync + 'static
+    {
+        // 原始载荷不一定是 u32，因此 new_raw 创建的观察者上 wait_for_change 只会超时
+        Self::register(guid, Arc::default(), Arc::default(), Box::new(move |data: &[u8], _at| handler(data)))
+    }
+
+    fn register(
+        guid: GUID,
+        change: Arc<ChangeSignal>,
+        history: Arc<EventHistory>,
+        callback: PowerSettingContext,
+    ) -> Result<Self, DeviceAwareError> {
+        // 暂停标志在系统线程上、调用用户回调之前检查；计时只覆盖实际调用的回调
+        let paused = Arc::new(AtomicBool::new(false));
+        let flag = paused.clone();
+        let slow = Arc::new(SlowCallbackWarning::default());
+        let timer = slow.clone();
+        let arrived = Arc::new(ArrivalSignal::default());
+        let signal = arrived.clone();
+        let callback: PowerSettingContext = Box::new(move |data: &[u8], at| {
+            signal.set();
+            if !flag.load(Ordering::Acquire) {
+                timer.time("PowerSettingObserver", || callback(data, at));
+            }
+        });
+
+        // 1. Double Boxing 策略，注册失败时由 OwnedContext 回滚
+        let registered = OwnedContext::register_with(callback, |context| {
+            // 2. 这里的 Context 必须是指向我们堆内存的指针。
+            // DEVICE_NOTIFY_CALLBACK 时 hRecipient 按文档就是 DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS 的地址，
+            // windows crate 把该参数统一声明为 HANDLE，所以这里只是类型上的包装，并非伪造句柄；
+            // 系统在注册时复制 Callback 与 Context，之后不再访问 params，因此放在栈上即可；
+            // 需要一直存活到注销的是 Context 指向的上下文
+            let mut params = DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS {
+                Callback: Some(Self::static_callback),
+                Context: context as *mut c_void,
+            };
+            unsafe {
+                RegisterPowerSettingNotification(
+                    HANDLE(&mut params as *mut _ as *mut c_void),
+                    &guid,
+                    DEVICE_NOTIFY_CALLBACK,
+                )
+            }
+        });
+
+        let (context, handle) = match registered {
+            Ok(registered) => registered,
+            Err(e) => {
+                log_error!("RegisterPowerSettingNotification failed for GUID {:?}: {:?}", guid, e);
+                return Err(DeviceAwareError::Registration {
+                    guid: Some(guid),
+                    api: "RegisterPowerSettingNotification",
+                    error: e,
+                });
+            }
+        };
+
+        Ok(PowerSettingObserver {
+            guid,
+            handle: Some(handle),
+            context,
+            change,
+            arrived,
+            history,
+            paused,
+            slow,
+        })
+    }
+
+    // 该观察者订阅的电源设置 GUID
+    pub fn guid(&self) -> GUID {
+        self.guid
+    }
+
+    // 通知是否仍处于注册状态；unsubscribe 之后返回 false
+    pub fn is_active(&self) -> bool {
+        self.handle.is_some()
+    }
+
+    /// RegisterPowerSettingNotification 返回的 HPOWERNOTIFY，供与其他 Win32 / WinRT 代码互操作；
+    /// unsubscribe 之后为 None
+    ///
+    /// # Safety
+    /// 句柄仍归观察者所有: 不要自行调用 UnregisterPowerSettingNotification，
+    /// 否则观察者 Drop 时会重复注销并释放仍可能被系统回调使用的上下文；也不要在观察者 Drop 之后继续使用
+    pub unsafe fn raw_handle(&self) -> Option<HPOWERNOTIFY> {
+        self.handle
+    }
+
+    // 暂停期间收到的通知直接丢弃 (不调用回调、不记录历史、不唤醒 wait_for_change)，
+    // 但注册保持不变，resume 后无需重新注册。窗口 / 服务句柄注册没有回调，暂停不起作用
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::Release);
+    }
+
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::Release);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Acquire)
+    }
+
+    // 阻塞等待下一次取值变化；超时返回 None
+    pub fn wait_for_change(&self, timeout: Duration) -> Option<u32> {
+        self.change.wait(timeout)
+    }
+
+    // 自检: 注册后系统通常会立即推送一次当前值。timeout 内 (已经收到过通知时立即返回) 没有任何通知
+    // 到达时，同步查询一次当前值并记录警告，返回 false；此时通知多半被策略屏蔽，建议改为定时调用
+    // current_value 轮询。窗口 / 服务句柄注册的通知不经过观察者，始终返回 false
+    pub fn verify_notifications(&self, timeout: Duration) -> bool {
+        if self.arrived.wait(timeout) {
+            return true;
+        }
+        log_warn!(
+            "PowerSettingObserver: no notification for GUID {:?} within {:?} of registration (synchronous query: {:?}); \
+             notifications appear non-functional, consider polling current_value instead",
+            self.guid, timeout, self.current_value()
+        );
+        false
+    }
+
+    // 保留最近 capacity 次 u32 通知 (时间, 值)，便于事后排查；0 表示关闭。
+    // new_raw / new_for_hwnd 创建的观察者没有 u32 值，不会记录
+    pub fn with_history(self, capacity: usize) -> Self {
+        self.history.set_capacity(capacity);
+        self
+    }
+
+    // 按时间先后返回保留的历史事件
+    pub fn history(&self) -> Vec<(SystemTime, u32)> {
+        self.history.snapshot()
+    }
+
+    // 用户回调耗时超过 threshold 时记录一条警告 (包含暂停检查之后的完整回调链)；
+    // 窗口 / 服务句柄注册没有回调，设置不起作用
+    pub fn with_slow_callback_warning(self, threshold: Duration) -> Self {
+        self.slow.set_threshold(threshold);
+        self
+    }
+
+    // 注册到调用方提供的窗口: 通知以 WM_POWERBROADCAST / PBT_POWERSETTINGCHANGE 的形式
+    // 投递到该窗口的 WndProc，适合已有消息循环的 GUI 框架。
+    // 返回的观察者没有回调，只负责在 unsubscribe / Drop 时注销；wait_for_change 不会被唤醒
+    pub fn new_for_hwnd(hwnd: HWND, guid: GUID) -> Result<Self, DeviceAwareError> {
+        Self::register_recipient(HANDLE(hwnd.0), DEVICE_NOTIFY_WINDOW_HANDLE, guid)
+    }
+
+    // 注册到服务状态句柄 (RegisterServiceCtrlHandlerEx 的返回值): 通知以 SERVICE_CONTROL_POWEREVENT
+    // 投递到服务的 HandlerEx，可用 service::power_setting_from_control 解码。
+    // 与 new_for_hwnd 一样没有回调，只负责注销
+    pub fn new_for_service_handle(status_handle: SERVICE_STATUS_HANDLE, guid: GUID) -> Result<Self, DeviceAwareError> {
+        Self::register_recipient(HANDLE(status_handle.0), DEVICE_NOTIFY_SERVICE_HANDLE, guid)
+    }
+
+    // 窗口 / 服务句柄注册: 由调用方的消息循环或 HandlerEx 接收通知，没有回调上下文
+    fn register_recipient(
+        recipient: HANDLE,
+        flags: REGISTER_NOTIFICATION_FLAGS,
+        guid: GUID,
+    ) -> Result<Self, DeviceAwareError> {
+        let handle = unsafe { RegisterPowerSettingNotification(recipient, &guid, flags) }
+            .inspect_err(|e| {
+                log_error!("RegisterPowerSettingNotification ({:?}) failed for GUID {:?}: {:?}", flags, guid, e);
+            })
+            .map_err(|error| DeviceAwareError::Registration {
+                guid: Some(guid),
+                api: "RegisterPowerSettingNotification",
+                error,
+            })?;
+
+        Ok(PowerSettingObserver {
+            guid,
+            handle: Some(handle),
+            context: OwnedContext::empty(),
+            change: Arc::default(),
+            arrived: Arc::default(),
+            history: Arc::default(),
+            paused: Arc::default(),
+            slow: Arc::default(),
+        })
+    }
+
+    // 基于 channel 的订阅：事件推送到 Receiver，由调用方在自己的线程里 recv()
+    pub fn new_channel(guid: GUID) -> Result<(Self, Receiver<u32>), DeviceAwareError> {
+        let (tx, rx) = mpsc::channel();
+        let observer = Self::new(guid, move |val| {
+            let _ = tx.send(val);
+        })?;
+        Ok((observer, rx))
+    }
+
+    // 同步查询当前订阅 GUID 的取值，不必等待下一次通知
+    // - GUID_ACDC_POWER_SOURCE: 0 = AC, 1 = 电池 (未知时返回 255)。结果来自 GetSystemPowerStatus，
+    //   它无法区分短期/UPS 供电，因此这里永远不会返回 2；需要识别 UPS 时以回调收到的值为准
+    // - GUID_POWER_SAVING_STATUS: 0 = 关闭, 1 = 开启
+    // 其他 GUID 没有对应的同步查询接口，返回 DeviceAwareError::Unsupported
+    pub fn current_value(&self) -> Result<u32, DeviceAwareError> {
+        let mut status = SYSTEM_POWER_STATUS::default();
+        if self.guid == GUID_ACDC_POWER_SOURCE {
+            unsafe { GetSystemPowerStatus(&mut status) }.map_err(DeviceAwareError::Query)?;
+            // ACLineStatus: 0 = 离线(电池), 1 = 在线(AC), 255 = 未知
+            Ok(match status.ACLineStatus {
+                1 => 0,
+                0 => 1,
+                other => other as u32,
+            })
+        } else if self.guid == GUID_POWER_SAVING_STATUS {
+            unsafe { GetSystemPowerStatus(&mut status) }.map_err(DeviceAwareError::Query)?;
+            // SystemStatusFlag: 1 = 节电模式开启
+            Ok((status.SystemStatusFlag != 0) as u32)
+        } else {
+            Err(DeviceAwareError::Unsupported)
+        }
+    }
+
+    // 主动注销通知并释放回调；之后 Drop 不再做任何事，重复调用也是安全的
+    // 与 EffectiveModeObserver::unsubscribe 相同，可以在自己的回调中调用
+    pub fn unsubscribe(&mut self) {
+        if is_in_callback(self.context.as_ptr())
+            && let Some(h) = self.handle.take()
+        {
+            let context = self.context.take();
+            defer_release("PowerSettingObserver", context, move || {
+                unsafe {
+                    let _ = UnregisterPowerSettingNotification(h);
+                }
+            });
+            return;
+        }
+        if let Some(h) = self.handle.take() {
+            log_debug!("PowerSettingObserver: unregistering GUID {:?}", self.guid);
+            unsafe {
+                let _ = UnregisterPowerSettingNotification(h);
+            }
+            // 4. 回收内存 (窗口句柄注册没有回调上下文，release 是空操作)
+            release_when_idle(&mut self.context);
+        }
+    }
+
+    unsafe extern "system" fn static_callback(
+        context: *const c_void,
+        type_: u32,
+        setting: *const c_void,
+    ) -> u32 {
+        let at = SystemTime::now();
+        if type_ == PBT_POWERSETTINGCHANGE && !context.is_null() && !setting.is_null() {
+            let p_setting = unsafe { &*(setting as *const POWERBROADCAST_SETTING) };
+            
+            // ---------------- 修复开始 ----------------
+
+            // 1. 获取 Data 字段的首地址指针
+            let data_ptr = p_setting.Data.as_ptr();
+
+            // 2. 根据 DataLength 手动构建切片，绕过 [u8; 1] 的静态限制
+            let data_slice = unsafe { std::slice::from_raw_parts(data_ptr, p_setting.DataLength as usize) };
+
+            // ---------------- 修复结束 ----------------
+
+            log_trace!("PowerSettingObserver: GUID {:?} -> {:?}", p_setting.PowerSetting, data_slice);
+
+            // 3. 恢复回调并调用，由回调自行解码载荷
+            let callback = unsafe { OwnedContext::<PowerSettingContext>::get(context) };
+            enter_callback(context, || {
+                guard_callback("PowerSettingObserver", || callback(data_slice, at));
+            });
+        }
+        0 
+    }
+}
+
+// SAFETY: 同 EffectiveModeObserver，上下文只由系统回调以只读方式使用，
+// 仅在 unsubscribe (需要 &mut self) 或 Drop 中释放一次；其余共享状态都在 Arc<Mutex> 中
+unsafe impl Send for PowerSettingObserver {}
+// SAFETY: &self 方法只读取 guid / handle 或经由 Mutex 访问 change，不会触碰上下文
+unsafe impl Sync for PowerSettingObserver {}
+
+// 编译期确认两个观察者可以跨线程移动、放进 Arc<Mutex<Vec<...>>>
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<EffectiveModeObserver>();
+    assert_send_sync::<PowerSettingObserver>();
+};
+
+impl Drop for PowerSettingObserver {
+    fn drop(&mut self) {
+        self.unsubscribe();
+    }
+}
+
+impl fmt::Debug for PowerSettingObserver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PowerSettingObserver")
+            .field("guid", &self.guid)
+            .field("active", &self.is_active())
+            .field("paused", &self.is_paused())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 在单独的栈帧里注册，返回后 params 所在的栈即失效
+    #[inline(never)]
+    fn register_in_own_frame(tx: mpsc::Sender<u32>) -> PowerSettingObserver {
+        PowerSettingObserver::new(GUID_ACDC_POWER_SOURCE, move |val| {
+            let _ = tx.send(val);
+        })
+        .unwrap()
+    }
+
+    #[inline(never)]
+    fn clobber_stack() {
+        let mut garbage = [0xA5u8; 16 * 1024];
+        std::hint::black_box(&mut garbage);
+    }
+
+    // 系统在注册后立即补发一次 GUID_ACDC_POWER_SOURCE 的当前值；params 的栈空间此时已被覆写，
+    // 若系统保留了 params 的地址，这次通知会取到错误的 Callback / Context
+    #[test]
+    fn subscribe_params_are_not_retained() {
+        let (tx, rx) = mpsc::channel();
+        let observer = register_in_own_frame(tx);
+        clobber_stack();
+        assert!(observer.verify_notifications(Duration::from_secs(5)));
+        let value = rx.recv_timeout(Duration::from_secs(1)).unwrap();
+        assert!(value <= 2, "unexpected GUID_ACDC_POWER_SOURCE value {}", value);
+    }
+}
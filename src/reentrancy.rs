@@ -0,0 +1,183 @@
+use std::cell::RefCell;
+use std::ffi::c_void;
+use std::mem::ManuallyDrop;
+use std::sync::{Condvar, Mutex};
+use std::thread;
+
+use crate::context::OwnedContext;
+
+// ============================================================================
+// 回调内注销: CM_Unregister_Notification 的文档明确说明它会等待正在执行的回调结束，
+// 因此不能在回调内调用 ("Do not call CM_Unregister_Notification from a notification callback.
+// Doing so may cause a deadlock")；电源类的 Unregister* 没有对此给出文档保证。
+// 所以这里不依赖注销函数的等待语义，自己记录哪些上下文的回调仍在执行:
+// - 线程局部 ACTIVE: 当前线程的回调栈，unsubscribe 据此判断自己是否在该上下文的回调里
+// - 全局 IN_FLIGHT: 所有线程上正在执行的回调，释放上下文之前等待对应的回调全部返回
+// 回调内 unsubscribe 时，把注销与释放交给一个短命线程，它注销后等待回调返回再释放上下文
+// ============================================================================
+
+thread_local! {
+    // 当前线程正在执行的回调 context (回调可能嵌套在其他观察者的回调中)
+    static ACTIVE: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+// 所有线程上正在执行的回调 context (允许重复，每个回调一项)；回调返回时通知 IDLE
+static IN_FLIGHT: Mutex<Vec<usize>> = Mutex::new(Vec::new());
+static IDLE: Condvar = Condvar::new();
+
+// 回调出栈: 放在 Drop 中，f 即使 panic 也总能出栈 (正常情况下 panic 已被 guard_callback 捕获)
+struct Frame(usize);
+
+impl Drop for Frame {
+    fn drop(&mut self) {
+        ACTIVE.with(|active| active.borrow_mut().pop());
+        let mut in_flight = IN_FLIGHT.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(i) = in_flight.iter().position(|&c| c == self.0) {
+            in_flight.swap_remove(i);
+        }
+        IDLE.notify_all();
+    }
+}
+
+// 在 context 对应的回调作用域内执行 f
+pub(crate) fn enter_callback<F: FnOnce()>(context: *const c_void, f: F) {
+    let context = context as usize;
+    IN_FLIGHT.lock().unwrap_or_else(|e| e.into_inner()).push(context);
+    ACTIVE.with(|active| active.borrow_mut().push(context));
+    let _frame = Frame(context);
+    f();
+}
+
+pub(crate) fn is_in_callback(context: *const c_void) -> bool {
+    !context.is_null() && ACTIVE.with(|active| active.borrow().contains(&(context as usize)))
+}
+
+// 等待所有线程上 context 的回调返回；不能在该 context 自己的回调里调用 (先用 is_in_callback 判断)
+fn wait_idle(context: *const c_void) {
+    if context.is_null() {
+        return;
+    }
+    let context = context as usize;
+    let mut in_flight = IN_FLIGHT.lock().unwrap_or_else(|e| e.into_inner());
+    while in_flight.contains(&context) {
+        in_flight = IDLE.wait(in_flight).unwrap_or_else(|e| e.into_inner());
+    }
+}
+
+// 注销之后释放上下文: 先等其他线程上仍在执行的回调返回
+pub(crate) fn release_when_idle<T>(context: &mut OwnedContext<T>) {
+    wait_idle(context.as_ptr());
+    context.release();
+}
+
+// 在独立线程上执行 unregister，并等回调 (包括发起本次注销的那个) 返回后释放上下文。
+// 线程创建失败时宁可泄漏回调上下文，也不在回调内释放: 两者包在 ManuallyDrop 中，创建失败时不会析构
+pub(crate) fn defer_release<T, F>(source: &'static str, context: OwnedContext<T>, unregister: F)
+where
+    T: Send + 'static,
+    F: FnOnce() + Send + 'static,
+{
+    log_debug!("{}: unsubscribe called from its own callback, deferring release", source);
+    let deferred = ManuallyDrop::new((context, unregister));
+    if let Err(e) = thread::Builder::new()
+        .name("device-aware-unsubscribe".to_string())
+        .spawn(move || {
+            let (mut context, unregister) = ManuallyDrop::into_inner(deferred);
+            unregister();
+            release_when_idle(&mut context);
+        })
+    {
+        log_error!("{}: failed to spawn release thread, leaking callback context: {:?}", source, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::mpsc;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use super::*;
+
+    fn ptr(n: usize) -> *const c_void {
+        n as *const c_void
+    }
+
+    // 嵌套回调: 内层回调里两个 context 都算 "在回调中"，出栈后逐层恢复
+    #[test]
+    fn callback_stack_tracks_nesting() {
+        let (outer, inner) = (ptr(0x1000), ptr(0x2000));
+        assert!(!is_in_callback(outer));
+        enter_callback(outer, || {
+            assert!(is_in_callback(outer));
+            assert!(!is_in_callback(inner));
+            enter_callback(inner, || {
+                assert!(is_in_callback(outer));
+                assert!(is_in_callback(inner));
+            });
+            assert!(!is_in_callback(inner));
+        });
+        assert!(!is_in_callback(outer));
+        assert!(!is_in_callback(std::ptr::null()));
+    }
+
+    // 回调栈是线程局部的: 其他线程上的回调不影响本线程的判断
+    #[test]
+    fn callback_stack_is_per_thread() {
+        let context = ptr(0x3000);
+        enter_callback(context, || {
+            let seen = thread::spawn(|| is_in_callback(ptr(0x3000))).join().unwrap();
+            assert!(!seen);
+        });
+    }
+
+    // 回调内的延迟释放: 先注销，等回调返回之后才释放上下文
+    #[test]
+    fn deferred_release_waits_for_callback_to_return() {
+        struct Released(mpsc::Sender<()>);
+        impl Drop for Released {
+            fn drop(&mut self) {
+                let _ = self.0.send(());
+            }
+        }
+
+        let (tx, rx) = mpsc::channel();
+        let (context, _) = OwnedContext::register_with(Released(tx), Ok::<_, ()>).unwrap();
+        let unregistered = Arc::new(AtomicBool::new(false));
+        let flag = unregistered.clone();
+        let ptr = context.as_ptr();
+        enter_callback(ptr, || {
+            assert!(is_in_callback(ptr));
+            defer_release("test", context, move || flag.store(true, Ordering::SeqCst));
+            assert!(rx.recv_timeout(Duration::from_millis(100)).is_err());
+        });
+        assert!(rx.recv_timeout(Duration::from_secs(5)).is_ok());
+        assert!(unregistered.load(Ordering::SeqCst));
+    }
+
+    // 在其他线程上注销: 释放要等仍在执行的回调返回
+    #[test]
+    fn release_waits_for_callbacks_on_other_threads() {
+        let released = Arc::new(AtomicBool::new(false));
+        let (mut context, _) = OwnedContext::register_with(released.clone(), Ok::<_, ()>).unwrap();
+        let ptr = context.as_ptr() as usize;
+        let (entered_tx, entered_rx) = mpsc::channel();
+        let (finish_tx, finish_rx) = mpsc::channel::<()>();
+        let callback = thread::spawn(move || {
+            enter_callback(ptr as *const c_void, || {
+                entered_tx.send(()).unwrap();
+                let _ = finish_rx.recv();
+            });
+        });
+        entered_rx.recv().unwrap();
+
+        let releaser = thread::spawn(move || release_when_idle(&mut context));
+        thread::sleep(Duration::from_millis(50));
+        assert!(!releaser.is_finished());
+        finish_tx.send(()).unwrap();
+        releaser.join().unwrap();
+        callback.join().unwrap();
+        assert_eq!(Arc::strong_count(&released), 1);
+    }
+}
@@ -0,0 +1,177 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::PowerEvent;
+
+// ============================================================================
+// 录制与回放: 事件以 NDJSON 保存 (与 DeviceAware --json 的输出相同，每行
+// {"ts":<毫秒时间戳>,"kind":"...","value":...})，回放时按原有间隔驱动同样的 PowerEvent 回调，
+// 便于在不插拔电源的情况下开发界面。kind / value 即 PowerEvent 的 serde 表示，编解码用 serde_json
+// ============================================================================
+
+#[derive(Serialize, Deserialize)]
+struct Record {
+    ts: u64,
+    #[serde(flatten)]
+    event: PowerEvent,
+}
+
+// 一行 NDJSON (不含换行)
+pub fn event_to_json(at: SystemTime, event: PowerEvent) -> String {
+    let ts = at.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis() as u64);
+    // 只含整数、字符串与布尔，序列化不会失败
+    serde_json::to_string(&Record { ts, event }).expect("PowerEvent record is always serializable")
+}
+
+// event_to_json 的逆操作；字段顺序不限，无法识别的行 (包括 monitor_power 等非 PowerEvent 的行) 返回 None
+pub fn event_from_json(line: &str) -> Option<(SystemTime, PowerEvent)> {
+    let record: Record = serde_json::from_str(line).ok()?;
+    Some((UNIX_EPOCH + Duration::from_millis(record.ts), record.event))
+}
+
+// 把实时事件追加写入文件，可直接作为 PowerMonitorBuilder::build 的回调使用。
+// 每行写完立即 flush，进程被终止时已写入的事件不会丢失
+pub struct EventRecorder {
+    out: Mutex<BufWriter<File>>,
+}
+
+impl EventRecorder {
+    // 文件已存在时在末尾追加
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = File::options().create(true).append(true).open(path)?;
+        Ok(EventRecorder { out: Mutex::new(BufWriter::new(file)) })
+    }
+
+    pub fn record(&self, event: PowerEvent) {
+        let line = event_to_json(SystemTime::now(), event);
+        let mut out = self.out.lock().unwrap_or_else(|e| e.into_inner());
+        if let Err(e) = writeln!(out, "{}", line).and_then(|_| out.flush()) {
+            log_warn!("EventRecorder: failed to write event: {:?}", e);
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ReplaySpeed {
+    // 按录制时的间隔回放
+    RealTime,
+    // 间隔除以倍数，例如 10.0 表示十倍速；非正数按 Instant 处理
+    Accelerated(f64),
+    // 不等待，逐个立即回放
+    Instant,
+}
+
+impl ReplaySpeed {
+    fn scale(self, gap: Duration) -> Duration {
+        match self {
+            ReplaySpeed::RealTime => gap,
+            // 极小的倍数会让结果超出 Duration 的范围，此时按最长等待处理而不是 panic
+            ReplaySpeed::Accelerated(factor) if factor > 0.0 => {
+                Duration::try_from_secs_f64(gap.as_secs_f64() / factor).unwrap_or(Duration::MAX)
+            }
+            ReplaySpeed::Accelerated(_) | ReplaySpeed::Instant => Duration::ZERO,
+        }
+    }
+}
+
+// 在当前线程上回放录制文件，返回回放的事件数。无法解析的行记录日志后跳过
+pub fn replay<F>(path: impl AsRef<Path>, speed: ReplaySpeed, handler: F) -> io::Result<usize>
+where F: Fn(PowerEvent)
+{
+    replay_from(BufReader::new(File::open(path)?), speed, handler)
+}
+
+// 与 replay 相同，但从任意 BufRead 读取 (例如标准输入)
+pub fn replay_from<R, F>(reader: R, speed: ReplaySpeed, handler: F) -> io::Result<usize>
+where
+    R: BufRead,
+    F: Fn(PowerEvent),
+{
+    let mut previous: Option<SystemTime> = None;
+    let mut count = 0;
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Some((at, event)) = event_from_json(&line) else {
+            log_debug!("replay: skipped unrecognized line: {}", line);
+            continue;
+        };
+        // 时间戳倒退 (例如多次录制拼接的文件) 时不等待
+        if let Some(gap) = previous.and_then(|p| at.duration_since(p).ok()) {
+            let wait = speed.scale(gap);
+            if !wait.is_zero() {
+                thread::sleep(wait);
+            }
+        }
+        previous = Some(at);
+        handler(event);
+        count += 1;
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EffectivePowerMode, PowerSourceType};
+
+    #[test]
+    fn events_round_trip_through_json() {
+        let at = UNIX_EPOCH + Duration::from_millis(1_700_000_000_123);
+        let events = [
+            PowerEvent::EffectiveMode(EffectivePowerMode::Balanced),
+            PowerEvent::EffectiveMode(EffectivePowerMode::Unknown(9)),
+            PowerEvent::PowerSource(PowerSourceType::AC),
+            PowerEvent::PowerSource(PowerSourceType::ShortTerm),
+            PowerEvent::SaverStatus(true),
+            PowerEvent::BatteryPercent(42),
+        ];
+        for event in events {
+            let line = event_to_json(at, event);
+            assert_eq!(event_from_json(&line), Some((at, event)), "{}", line);
+        }
+    }
+
+    // 录制格式保持不变: 旧文件 (字段顺序任意) 仍可回放
+    #[test]
+    fn record_format_is_ts_kind_value() {
+        let at = UNIX_EPOCH + Duration::from_millis(5);
+        assert_eq!(
+            event_to_json(at, PowerEvent::PowerSource(PowerSourceType::AC)),
+            r#"{"ts":5,"kind":"power_source","value":"ac"}"#
+        );
+        assert_eq!(
+            event_to_json(at, PowerEvent::EffectiveMode(EffectivePowerMode::Unknown(7))),
+            r#"{"ts":5,"kind":"effective_mode","value":{"unknown":7}}"#
+        );
+        assert_eq!(
+            event_from_json(r#"{"value":false,"kind":"saver_status","ts":5}"#),
+            Some((at, PowerEvent::SaverStatus(false)))
+        );
+    }
+
+    #[test]
+    fn unrecognized_lines_are_rejected() {
+        assert_eq!(event_from_json(r#"{"ts":1,"kind":"monitor_power","value":"on"}"#), None);
+        assert_eq!(event_from_json(r#"{"ts":1,"kind":"power_source","value":"solar"}"#), None);
+        assert_eq!(event_from_json(r#"{"kind":"saver_status","value":true}"#), None);
+        assert_eq!(event_from_json("not json"), None);
+    }
+
+    #[test]
+    fn scale_does_not_overflow_for_tiny_factors() {
+        let gap = Duration::from_secs(1);
+        assert_eq!(ReplaySpeed::Accelerated(1e-20).scale(gap), Duration::MAX);
+        assert_eq!(ReplaySpeed::Accelerated(10.0).scale(gap), Duration::from_millis(100));
+        assert_eq!(ReplaySpeed::Accelerated(0.0).scale(gap), Duration::ZERO);
+        assert_eq!(ReplaySpeed::RealTime.scale(gap), gap);
+    }
+}
@@ -0,0 +1,98 @@
+use std::ffi::c_void;
+
+use windows_core::GUID;
+use windows::Win32::System::Power::POWERBROADCAST_SETTING;
+use windows::Win32::System::Services::{SERVICE_CONTROL_POWEREVENT, SERVICE_STATUS_HANDLE};
+use windows::Win32::UI::WindowsAndMessaging::PBT_POWERSETTINGCHANGE;
+
+use crate::guids::{
+    GUID_ACDC_POWER_SOURCE, GUID_BATTERY_PERCENTAGE_REMAINING, GUID_POWER_SAVING_STATUS,
+};
+use crate::{DeviceAwareError, PowerEvent, PowerSettingObserver, PowerSourceType};
+
+// ============================================================================
+// Windows 服务模式下的电源通知
+//
+// 电源设置通知有三种投递方式，区别在于 RegisterPowerSettingNotification 的注册目标:
+// - DEVICE_NOTIFY_CALLBACK (PowerSettingObserver::new 等): 系统在线程池线程上直接调用回调，
+//   控制台程序和服务都可以使用，PowerMonitor 默认即是这种方式
+// - DEVICE_NOTIFY_WINDOW_HANDLE (new_for_hwnd): 以 WM_POWERBROADCAST 投递到窗口，
+//   需要消息循环；session 0 中的服务通常没有窗口，不适用
+// - DEVICE_NOTIFY_SERVICE_HANDLE (new_for_service_handle / 本模块): 以 SERVICE_CONTROL_POWEREVENT 投递到服务的
+//   HandlerEx，与停止/关机等控制请求在同一个线程上串行处理。
+//   服务必须在 SERVICE_STATUS 中声明 SERVICE_ACCEPT_POWEREVENT
+//
+// 注意滑块位置 (EffectiveModeObserver) 只有回调方式，服务中仍需直接使用观察者
+// ============================================================================
+
+pub struct ServicePowerNotifications {
+    // 仅用于保持注册存活，Drop 时自动注销
+    observers: Vec<PowerSettingObserver>,
+}
+
+impl ServicePowerNotifications {
+    // 以服务状态句柄注册一组 GUID；任意一个失败则注销已注册的并返回错误
+    pub fn register(status_handle: SERVICE_STATUS_HANDLE, guids: &[GUID]) -> Result<Self, DeviceAwareError> {
+        let observers = guids
+            .iter()
+            .map(|&guid| PowerSettingObserver::new_for_service_handle(status_handle, guid))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(ServicePowerNotifications { observers })
+    }
+
+    // 订阅 PowerEvent 覆盖的三个电源设置: AC/DC、节电模式与剩余电量
+    pub fn for_monitor(status_handle: SERVICE_STATUS_HANDLE) -> Result<Self, DeviceAwareError> {
+        Self::register(status_handle, &[
+            GUID_ACDC_POWER_SOURCE,
+            GUID_POWER_SAVING_STATUS,
+            GUID_BATTERY_PERCENTAGE_REMAINING,
+        ])
+    }
+
+    // 已注册的 GUID
+    pub fn guids(&self) -> impl Iterator<Item = GUID> + '_ {
+        self.observers.iter().map(PowerSettingObserver::guid)
+    }
+}
+
+/// 在服务的 HandlerEx 中调用，把 SERVICE_CONTROL_POWEREVENT / PBT_POWERSETTINGCHANGE
+/// 还原为 (GUID, 载荷)；其他控制码返回 None
+///
+/// # Safety
+/// 参数必须原样来自 HandlerEx，event_data 在 HandlerEx 返回前有效，返回的切片不能超出这个范围
+pub unsafe fn power_setting_from_control<'a>(
+    control: u32,
+    event_type: u32,
+    event_data: *const c_void,
+) -> Option<(GUID, &'a [u8])> {
+    if control != SERVICE_CONTROL_POWEREVENT || event_type != PBT_POWERSETTINGCHANGE || event_data.is_null() {
+        return None;
+    }
+    let setting = unsafe { &*(event_data as *const POWERBROADCAST_SETTING) };
+    let data = unsafe { std::slice::from_raw_parts(setting.Data.as_ptr(), setting.DataLength as usize) };
+    log_trace!("ServicePowerNotifications: GUID {:?} -> {:?}", setting.PowerSetting, data);
+    Some((setting.PowerSetting, data))
+}
+
+/// 与 power_setting_from_control 相同，但直接解码为 PowerEvent，
+/// 便于与 PowerMonitor 共用同一个事件处理函数
+///
+/// # Safety
+/// 同 power_setting_from_control
+pub unsafe fn power_event_from_control(
+    control: u32,
+    event_type: u32,
+    event_data: *const c_void,
+) -> Option<PowerEvent> {
+    let (guid, data) = unsafe { power_setting_from_control(control, event_type, event_data)? };
+    let val = u32::from_ne_bytes(data.try_into().ok()?);
+    if guid == GUID_ACDC_POWER_SOURCE {
+        Some(PowerEvent::PowerSource(PowerSourceType::from(val)))
+    } else if guid == GUID_POWER_SAVING_STATUS {
+        Some(PowerEvent::SaverStatus(val != 0))
+    } else if guid == GUID_BATTERY_PERCENTAGE_REMAINING {
+        Some(PowerEvent::BatteryPercent(val))
+    } else {
+        None
+    }
+}
@@ -0,0 +1,121 @@
+// ============================================================================
+// 会话信号: 在场状态与锁定/解锁
+//
+// 两类信号的投递方式不同:
+// - 会话内的用户在场状态 (GUID_SESSION_USER_PRESENCE) 是普通电源设置，
+//   SessionPresenceObserver 以回调方式注册即可，不需要窗口
+// - 锁定/解锁/登录/断开只能通过 WTSRegisterSessionNotification 获得，系统以 WM_WTSSESSION_CHANGE
+//   投递到窗口。SessionObserver::new 使用内部的隐藏窗口及其消息线程；已有窗口的程序可以用
+//   new_for_hwnd，并在窗口过程中用 session_event_from_message 解码。服务则在 HandlerEx 中处理 SERVICE_CONTROL_SESSIONCHANGE
+// ============================================================================
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum SessionEvent {
+    Lock,
+    Unlock,
+    // 用户登录，或控制台 / 远程客户端连接到本会话
+    LogonConnect,
+    // 控制台 / 远程客户端从本会话断开
+    Disconnect,
+    Logoff,
+    // 其他 WTS_* 事件码 (如 WTS_SESSION_REMOTE_CONTROL)，保留原始值
+    Other(u32),
+}
+
+// WM_WTSSESSION_CHANGE 的 wParam 取值 (WTS_CONSOLE_CONNECT = 1 ... WTS_SESSION_UNLOCK = 8)
+impl From<u32> for SessionEvent {
+    fn from(val: u32) -> Self {
+        match val {
+            1 | 3 | 5 => SessionEvent::LogonConnect,
+            2 | 4 => SessionEvent::Disconnect,
+            6 => SessionEvent::Logoff,
+            7 => SessionEvent::Lock,
+            8 => SessionEvent::Unlock,
+            other => SessionEvent::Other(other),
+        }
+    }
+}
+
+#[cfg(all(windows, not(feature = "mock")))]
+pub use imp::{session_event_from_message, SessionObserver};
+
+#[cfg(all(windows, not(feature = "mock")))]
+mod imp {
+    use std::sync::Arc;
+
+    use windows::Win32::Foundation::{HWND, WPARAM};
+    use windows::Win32::System::RemoteDesktop::{
+        WTSRegisterSessionNotification, WTSUnRegisterSessionNotification, NOTIFY_FOR_THIS_SESSION,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::WM_WTSSESSION_CHANGE;
+
+    use super::SessionEvent;
+    use crate::message_window::MessageWindow;
+    use crate::DeviceAwareError;
+
+    // 为窗口注册本会话的 WM_WTSSESSION_CHANGE 通知；窗口必须在观察者 Drop 之前保持有效
+    pub struct SessionObserver {
+        hwnd: Option<HWND>,
+        // new 创建的隐藏窗口；Drop 时先注销通知，再随字段一起销毁窗口
+        _window: Option<MessageWindow>,
+    }
+
+    impl SessionObserver {
+        // 使用内部隐藏窗口接收通知，回调在该窗口的消息线程上执行
+        pub fn new<F>(handler: F) -> Result<Self, DeviceAwareError>
+        where F: Fn(SessionEvent) + Send + Sync + 'static
+        {
+            let window = MessageWindow::new()?;
+            window.on_message(WM_WTSSESSION_CHANGE, Arc::new(move |wparam, _| {
+                handler(SessionEvent::from(wparam.0 as u32));
+            }));
+            let mut observer = Self::new_for_hwnd(window.hwnd())?;
+            observer._window = Some(window);
+            Ok(observer)
+        }
+
+        pub fn new_for_hwnd(hwnd: HWND) -> Result<Self, DeviceAwareError> {
+            unsafe { WTSRegisterSessionNotification(hwnd, NOTIFY_FOR_THIS_SESSION) }
+                .map_err(|error| DeviceAwareError::Registration {
+                    guid: None,
+                    api: "WTSRegisterSessionNotification",
+                    error,
+                })?;
+            log_debug!("SessionObserver: registered session notifications for {:?}", hwnd);
+            Ok(SessionObserver { hwnd: Some(hwnd), _window: None })
+        }
+
+        pub fn is_active(&self) -> bool {
+            self.hwnd.is_some()
+        }
+
+        // 主动注销；之后 Drop 不再做任何事，重复调用也是安全的
+        pub fn unsubscribe(&mut self) {
+            if let Some(hwnd) = self.hwnd.take() {
+                log_debug!("SessionObserver: unregistering session notifications for {:?}", hwnd);
+                let _ = unsafe { WTSUnRegisterSessionNotification(hwnd) };
+            }
+        }
+    }
+
+    // SAFETY: HWND 只是交给系统注销用的不透明句柄，不会在 Rust 侧被解引用
+    unsafe impl Send for SessionObserver {}
+    // SAFETY: &self 方法只读取句柄是否存在
+    unsafe impl Sync for SessionObserver {}
+
+    impl Drop for SessionObserver {
+        fn drop(&mut self) {
+            self.unsubscribe();
+        }
+    }
+
+    // 在窗口过程中调用: WM_WTSSESSION_CHANGE 还原为 SessionEvent，其他消息返回 None
+    pub fn session_event_from_message(msg: u32, wparam: WPARAM) -> Option<SessionEvent> {
+        if msg != WM_WTSSESSION_CHANGE {
+            return None;
+        }
+        Some(SessionEvent::from(wparam.0 as u32))
+    }
+}
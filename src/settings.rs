@@ -0,0 +1,262 @@
+use std::sync::Arc;
+
+use windows_core::GUID;
+
+use crate::guids::{
+    GUID_CONSOLE_DISPLAY_STATE, GUID_ENERGY_SAVER_STATUS, GUID_IDLE_BACKGROUND_TASK, GUID_LIDSWITCH_STATE_CHANGE,
+    GUID_MONITOR_POWER_ON, GUID_MAX_POWER_SAVINGS, GUID_MIN_POWER_SAVINGS, GUID_PROCESSOR_THROTTLE_MAXIMUM,
+    GUID_PROCESSOR_THROTTLE_MINIMUM, GUID_TYPICAL_POWER_SAVINGS,
+};
+use crate::{DeviceAwareError, PowerSettingObserver};
+
+// ============================================================================
+// 特定电源设置的类型化解码
+// ============================================================================
+
+// 显示器电源状态
+// - GUID_MONITOR_POWER_ON (已弃用): 0 = 关, 1 = 开
+// - GUID_CONSOLE_DISPLAY_STATE: 0 = 关, 1 = 开, 2 = 变暗 (变暗时屏幕仍然点亮，视为 On)
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum MonitorPowerState {
+    On,
+    Off,
+}
+
+impl From<u32> for MonitorPowerState {
+    fn from(val: u32) -> Self {
+        match val {
+            0 => MonitorPowerState::Off,
+            _ => MonitorPowerState::On,
+        }
+    }
+}
+
+// GUID_CONSOLE_DISPLAY_STATE 的三态取值: 0 = 关, 1 = 开, 2 = 变暗
+// 其余未定义的取值按 On 处理，与 MonitorPowerState 保持一致
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum DisplayState {
+    Off,
+    On,
+    Dimmed,
+}
+
+impl From<u32> for DisplayState {
+    fn from(val: u32) -> Self {
+        match val {
+            0 => DisplayState::Off,
+            2 => DisplayState::Dimmed,
+            _ => DisplayState::On,
+        }
+    }
+}
+
+// 笔记本盖子状态 (GUID_LIDSWITCH_STATE_CHANGE): 0 = 合上, 1 = 打开
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum LidState {
+    Closed,
+    Open,
+}
+
+impl From<u32> for LidState {
+    fn from(val: u32) -> Self {
+        match val {
+            0 => LidState::Closed,
+            _ => LidState::Open,
+        }
+    }
+}
+
+// Windows 11 节能模式状态，来自 GUID_ENERGY_SAVER_STATUS:
+// 0 = ENERGY_SAVER_OFF, 1 = ENERGY_SAVER_STANDARD, 2 = ENERGY_SAVER_HIGH_SAVINGS。
+// 旧的 GUID_POWER_SAVING_STATUS 只给出开/关，HighSavings 在那里同样报告为开
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum EnergySaverStatus {
+    Off,
+    Standard,
+    HighSavings,
+    Unknown(u32),
+}
+
+impl From<u32> for EnergySaverStatus {
+    fn from(val: u32) -> Self {
+        match val {
+            0 => EnergySaverStatus::Off,
+            1 => EnergySaverStatus::Standard,
+            2 => EnergySaverStatus::HighSavings,
+            other => EnergySaverStatus::Unknown(other),
+        }
+    }
+}
+
+impl EnergySaverStatus {
+    // 与旧的节电模式开关含义一致: 除 Off 以外都视为开启，未知值也按开启处理
+    pub fn is_on(self) -> bool {
+        self != EnergySaverStatus::Off
+    }
+}
+
+// 电池告警级别，对应 GUID_BATTERY_DISCHARGE_LEVEL_1 (低) 与 GUID_BATTERY_DISCHARGE_LEVEL_0 (严重)
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum BatteryAlarm {
+    Low,
+    Critical,
+}
+
+// 处理器性能限制百分比 (0-100)，超出范围的值按 100 处理
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ThrottlePercent(pub u8);
+
+impl From<u32> for ThrottlePercent {
+    fn from(val: u32) -> Self {
+        ThrottlePercent(val.min(100) as u8)
+    }
+}
+
+impl ThrottlePercent {
+    // 上限低于 100% 即表示处理器因电源策略被限速
+    pub fn is_throttled(self) -> bool {
+        self.0 < 100
+    }
+}
+
+// 用户在场状态，来自 GUID_GLOBAL_USER_PRESENCE
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum UserPresence {
+    Present,
+    Inactive,
+    Unknown,
+}
+
+impl From<u32> for UserPresence {
+    fn from(val: u32) -> Self {
+        match val {
+            0 => UserPresence::Present,
+            2 => UserPresence::Inactive,
+            _ => UserPresence::Unknown,
+        }
+    }
+}
+
+// 电源计划的 "个性"，来自 GUID_POWERSCHEME_PERSONALITY 的 GUID 载荷
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum PowerSchemePersonality {
+    HighPerformance,
+    Balanced,
+    PowerSaver,
+    Unknown,
+}
+
+impl From<GUID> for PowerSchemePersonality {
+    fn from(guid: GUID) -> Self {
+        if guid == GUID_MIN_POWER_SAVINGS {
+            PowerSchemePersonality::HighPerformance
+        } else if guid == GUID_TYPICAL_POWER_SAVINGS {
+            PowerSchemePersonality::Balanced
+        } else if guid == GUID_MAX_POWER_SAVINGS {
+            PowerSchemePersonality::PowerSaver
+        } else {
+            PowerSchemePersonality::Unknown
+        }
+    }
+}
+
+impl PowerSettingObserver {
+    // 订阅显示器开/关。优先使用 GUID_CONSOLE_DISPLAY_STATE，
+    // 在不支持它的旧系统上退回已弃用的 GUID_MONITOR_POWER_ON
+    pub fn new_monitor_power<F>(handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(MonitorPowerState) + Send + Sync + 'static
+    {
+        let handler = Arc::new(handler);
+        let h = handler.clone();
+        match Self::new(GUID_CONSOLE_DISPLAY_STATE, move |val| h(MonitorPowerState::from(val))) {
+            Ok(observer) => Ok(observer),
+            Err(e) => {
+                log_debug!("GUID_CONSOLE_DISPLAY_STATE unavailable ({:?}), falling back to GUID_MONITOR_POWER_ON", e);
+                Self::new(GUID_MONITOR_POWER_ON, move |val| handler(MonitorPowerState::from(val)))
+            }
+        }
+    }
+
+    // 订阅 GUID_CONSOLE_DISPLAY_STATE，可区分变暗状态 (载荷同样是 4 字节 u32)
+    pub fn new_display_state<F>(handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(DisplayState) + Send + Sync + 'static
+    {
+        Self::new(GUID_CONSOLE_DISPLAY_STATE, move |val| handler(DisplayState::from(val)))
+    }
+
+    // 订阅盖子开合。注意：台式机等没有盖子开关的设备上注册也可能成功，
+    // 但永远不会收到通知，调用方不应假设一定会有事件
+    pub fn new_lid_state<F>(handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(LidState) + Send + Sync + 'static
+    {
+        Self::new(GUID_LIDSWITCH_STATE_CHANGE, move |val| handler(LidState::from(val)))
+    }
+
+    // 订阅 Windows 11 节能模式 (GUID_ENERGY_SAVER_STATUS)，可区分标准与高强度节能。
+    // 不认识该 GUID 的旧系统上注册会失败，此时可退回 PowerSaverObserver 只获取开/关
+    pub fn new_energy_saver_status<F>(handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(EnergySaverStatus) + Send + Sync + 'static
+    {
+        Self::new(GUID_ENERGY_SAVER_STATUS, move |val| handler(EnergySaverStatus::from(val)))
+    }
+
+    // 订阅当前电源计划的最大处理器状态 (GUID_PROCESSOR_THROTTLE_MAXIMUM)
+    pub fn new_processor_throttle_max<F>(handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(ThrottlePercent) + Send + Sync + 'static
+    {
+        Self::new(GUID_PROCESSOR_THROTTLE_MAXIMUM, move |val| handler(ThrottlePercent::from(val)))
+    }
+
+    // 订阅当前电源计划的最小处理器状态 (GUID_PROCESSOR_THROTTLE_MINIMUM)
+    pub fn new_processor_throttle_min<F>(handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(ThrottlePercent) + Send + Sync + 'static
+    {
+        Self::new(GUID_PROCESSOR_THROTTLE_MINIMUM, move |val| handler(ThrottlePercent::from(val)))
+    }
+
+    // 订阅后台空闲任务时机 (GUID_IDLE_BACKGROUND_TASK)。该 GUID 是触发器而非设置值:
+    // 每次通知表示 "现在适合运行后台任务"，不携带有效载荷，也没有 "时机结束" 的通知，
+    // 因此回调没有参数。载荷长度不固定，基于 new_raw 注册，也就不记录历史、不唤醒 wait_for_change
+    pub fn new_background_idle<F>(handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn() + Send + Sync + 'static
+    {
+        Self::new_raw(GUID_IDLE_BACKGROUND_TASK, move |_data| handler())
+    }
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    // 触发器不论载荷为何都只表示一次 "开始"
+    #[test]
+    fn background_idle_fires_for_any_payload() {
+        let fired = Arc::new(AtomicUsize::new(0));
+        let counter = fired.clone();
+        let observer = PowerSettingObserver::new_background_idle(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        })
+        .unwrap();
+
+        observer.inject_raw(&[]);
+        observer.inject(0);
+        assert_eq!(fired.load(Ordering::SeqCst), 2);
+    }
+}
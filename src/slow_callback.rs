@@ -0,0 +1,35 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+// 诊断用: 用户回调运行在系统通知线程上，耗时过长会推迟其他通知的投递。
+// 设置阈值后为每次回调计时，超过阈值时记录警告；阈值为 0 (默认) 表示不计时
+#[derive(Default)]
+pub(crate) struct SlowCallbackWarning {
+    threshold_nanos: AtomicU64,
+}
+
+impl SlowCallbackWarning {
+    pub(crate) fn set_threshold(&self, threshold: Duration) {
+        let nanos = u64::try_from(threshold.as_nanos()).unwrap_or(u64::MAX);
+        self.threshold_nanos.store(nanos, Ordering::Release);
+    }
+
+    pub(crate) fn time<F: FnOnce()>(&self, source: &str, f: F) {
+        let threshold = self.threshold_nanos.load(Ordering::Acquire);
+        if threshold == 0 {
+            f();
+            return;
+        }
+        let start = Instant::now();
+        f();
+        let elapsed = start.elapsed();
+        if elapsed > Duration::from_nanos(threshold) {
+            log_warn!(
+                "{}: user callback took {:?} (threshold {:?}); avoid blocking work on the notification thread",
+                source,
+                elapsed,
+                Duration::from_nanos(threshold)
+            );
+        }
+    }
+}
@@ -0,0 +1,300 @@
+use std::time::Duration;
+
+use crate::{
+    DeviceAwareError, EffectiveModeObserver, EffectivePowerMode, PowerEvent, PowerSchemePersonality,
+    PowerSourceType,
+};
+
+// ============================================================================
+// 一次性查询: GetSystemPowerStatus
+// ============================================================================
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct SystemPowerStatus {
+    // 0 = 离线(电池), 1 = 在线(AC), 255 = 未知
+    pub ac_line_status: u8,
+    // 位标志: 1 = 高, 2 = 低, 4 = 严重, 8 = 充电中, 128 = 无电池, 255 = 未知
+    pub battery_flag: u8,
+    // 系统返回 255 表示未知，映射为 None
+    pub battery_life_percent: Option<u8>,
+    // 预计剩余续航 (UPS 供电时即 UPS 可支撑的时间)；系统返回 0xFFFFFFFF 表示未知或接通电源，映射为 None
+    pub battery_life_time: Option<Duration>,
+    // 充满电时的预计续航，同样以 0xFFFFFFFF 表示未知
+    pub battery_full_life_time: Option<Duration>,
+    // 1 = 节电模式开启
+    pub system_status_flag: u8,
+}
+
+impl SystemPowerStatus {
+    // 由 ac_line_status 推导电源类型。GetSystemPowerStatus 无法区分短期/UPS 供电，
+    // 需要区分时请订阅 GUID_ACDC_POWER_SOURCE
+    pub fn power_source(&self) -> PowerSourceType {
+        match self.ac_line_status {
+            1 => PowerSourceType::AC,
+            0 => PowerSourceType::Battery,
+            _ => PowerSourceType::Unknown,
+        }
+    }
+}
+
+#[cfg(all(windows, not(feature = "mock")))]
+fn seconds(val: u32) -> Option<Duration> {
+    match val {
+        u32::MAX => None,
+        secs => Some(Duration::from_secs(secs as u64)),
+    }
+}
+
+#[cfg(all(windows, not(feature = "mock")))]
+pub fn system_power_status() -> Result<SystemPowerStatus, DeviceAwareError> {
+    use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    let mut status = SYSTEM_POWER_STATUS::default();
+    unsafe { GetSystemPowerStatus(&mut status) }.map_err(DeviceAwareError::Query)?;
+
+    Ok(SystemPowerStatus {
+        ac_line_status: status.ACLineStatus,
+        battery_flag: status.BatteryFlag,
+        battery_life_percent: match status.BatteryLifePercent {
+            255 => None,
+            percent => Some(percent),
+        },
+        battery_life_time: seconds(status.BatteryLifeTime),
+        battery_full_life_time: seconds(status.BatteryFullLifeTime),
+        system_status_flag: status.SystemStatusFlag,
+    })
+}
+
+// ============================================================================
+// PowerState: 一次调用拿到全部当前读数，用于界面的首次渲染
+// ============================================================================
+
+// 注册后系统会立即推送一次当前模式，最多等待这么久
+const EFFECTIVE_MODE_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct PowerState {
+    pub source: PowerSourceType,
+    pub saver_on: bool,
+    // 尚未收到系统推送 (或超时) 时为 None；Unknown(n) 表示系统报告了无法识别的位置
+    pub effective_mode: Option<EffectivePowerMode>,
+    pub battery_percent: Option<u8>,
+}
+
+impl PowerState {
+    // 尚未取得任何读数时的初始值
+    pub(crate) fn unknown() -> Self {
+        PowerState {
+            source: PowerSourceType::Unknown,
+            saver_on: false,
+            effective_mode: None,
+            battery_percent: None,
+        }
+    }
+
+    // 用一个事件更新对应字段
+    pub(crate) fn apply(&mut self, event: PowerEvent) {
+        match event {
+            PowerEvent::EffectiveMode(mode) => self.effective_mode = Some(mode),
+            PowerEvent::PowerSource(source) => self.source = source,
+            PowerEvent::SaverStatus(is_on) => self.saver_on = is_on,
+            PowerEvent::BatteryPercent(percent) => self.battery_percent = Some(percent.min(100) as u8),
+        }
+    }
+
+    // 事件携带的值与对应字段相同，即 apply 不会改变状态
+    pub(crate) fn reflects(&self, event: PowerEvent) -> bool {
+        match event {
+            PowerEvent::EffectiveMode(mode) => self.effective_mode == Some(mode),
+            PowerEvent::PowerSource(source) => self.source == source,
+            PowerEvent::SaverStatus(is_on) => self.saver_on == is_on,
+            PowerEvent::BatteryPercent(percent) => self.battery_percent == Some(percent.min(100) as u8),
+        }
+    }
+}
+
+// 同步查询当前电源状态。
+// 没有直接查询滑块位置的 API，这里临时注册一次 EffectiveModeObserver，取系统推送的首个值
+pub fn current_power_state() -> Result<PowerState, DeviceAwareError> {
+    let status = system_power_status()?;
+
+    let (observer, rx) = EffectiveModeObserver::new_channel()?;
+    let effective_mode = rx.recv_timeout(EFFECTIVE_MODE_TIMEOUT).ok();
+    if effective_mode.is_none() {
+        log_warn!("current_power_state: no effective power mode received within {:?}", EFFECTIVE_MODE_TIMEOUT);
+    }
+    drop(observer);
+
+    Ok(PowerState {
+        source: status.power_source(),
+        saver_on: status.system_status_flag != 0,
+        effective_mode,
+        battery_percent: status.battery_life_percent,
+    })
+}
+
+// ============================================================================
+// 一次性查询: CallNtPowerInformation(SystemBatteryState)
+// 比 GetSystemPowerStatus 更细: 包含容量与实时充放电功率
+// ============================================================================
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BatteryState {
+    pub ac_online: bool,
+    pub battery_present: bool,
+    pub charging: bool,
+    pub discharging: bool,
+    // 剩余容量 (mWh)
+    pub capacity: u32,
+    // 满电容量 (mWh)
+    pub max_capacity: u32,
+    // 当前功率 (mW)，放电时为负，充电时为正；系统不支持时通常为 0
+    pub rate: i32,
+    // 系统估算的剩余续航；未知或接通电源时为 None
+    pub estimated_time: Option<Duration>,
+}
+
+impl BatteryState {
+    // 由剩余容量与满电容量计算的百分比；没有电池或满电容量为 0 时为 None
+    pub fn percent(&self) -> Option<u8> {
+        if !self.battery_present || self.max_capacity == 0 {
+            return None;
+        }
+        Some((self.capacity as u64 * 100 / self.max_capacity as u64).min(100) as u8)
+    }
+}
+
+#[cfg(all(windows, not(feature = "mock")))]
+pub fn battery_state() -> Result<BatteryState, DeviceAwareError> {
+    use windows::Win32::System::Power::{CallNtPowerInformation, SystemBatteryState, SYSTEM_BATTERY_STATE};
+
+    let mut state = SYSTEM_BATTERY_STATE::default();
+    unsafe {
+        CallNtPowerInformation(
+            SystemBatteryState,
+            None,
+            0,
+            Some(&mut state as *mut _ as *mut _),
+            std::mem::size_of::<SYSTEM_BATTERY_STATE>() as u32,
+        )
+    }
+    .ok()
+    .map_err(DeviceAwareError::Query)?;
+
+    Ok(BatteryState {
+        ac_online: state.AcOnLine.0 != 0,
+        battery_present: state.BatteryPresent.0 != 0,
+        charging: state.Charging.0 != 0,
+        discharging: state.Discharging.0 != 0,
+        capacity: state.RemainingCapacity,
+        max_capacity: state.MaxCapacity,
+        // 系统以 DWORD 返回有符号功率
+        rate: state.Rate as i32,
+        estimated_time: seconds(state.EstimatedTime),
+    })
+}
+
+// 非 Windows 平台没有可查询的电池状态
+#[cfg(all(not(windows), not(feature = "mock")))]
+pub fn battery_state() -> Result<BatteryState, DeviceAwareError> {
+    Err(DeviceAwareError::Unsupported)
+}
+
+// 同步查询当前电源计划的个性 (高性能 / 平衡 / 节能)。
+// 活动计划本身就是三个内置计划之一时直接映射；自定义计划再读取其 GUID_POWERSCHEME_PERSONALITY 设置，
+// 该值不是 16 字节 GUID 时返回 Unknown
+#[cfg(all(windows, not(feature = "mock")))]
+pub fn current_power_scheme() -> Result<PowerSchemePersonality, DeviceAwareError> {
+    use windows_core::GUID;
+    use windows::Win32::Foundation::{LocalFree, HLOCAL};
+    use windows::Win32::System::Power::{PowerGetActiveScheme, PowerReadACValue};
+    use windows::Win32::System::Registry::HKEY;
+
+    use crate::guids::{GUID_POWERSCHEME_PERSONALITY, NO_SUBGROUP_GUID};
+    use crate::observer_ext::decode_guid;
+
+    let mut active: *mut GUID = std::ptr::null_mut();
+    unsafe { PowerGetActiveScheme(HKEY::default(), &mut active) }
+        .ok()
+        .map_err(DeviceAwareError::Query)?;
+    // 返回的 GUID 由系统分配，复制出来后用 LocalFree 释放
+    let scheme = unsafe { *active };
+    unsafe {
+        let _ = LocalFree(HLOCAL(active as *mut _));
+    }
+
+    let personality = PowerSchemePersonality::from(scheme);
+    if personality != PowerSchemePersonality::Unknown {
+        return Ok(personality);
+    }
+
+    let mut buffer = [0u8; 16];
+    let mut size = buffer.len() as u32;
+    unsafe {
+        PowerReadACValue(
+            HKEY::default(),
+            Some(&scheme),
+            Some(&NO_SUBGROUP_GUID),
+            Some(&GUID_POWERSCHEME_PERSONALITY),
+            None,
+            Some(buffer.as_mut_ptr()),
+            Some(&mut size),
+        )
+    }
+    .ok()
+    .map_err(DeviceAwareError::Query)?;
+
+    let data = &buffer[..(size as usize).min(buffer.len())];
+    Ok(decode_guid(data).map_or(PowerSchemePersonality::Unknown, PowerSchemePersonality::from))
+}
+
+// mock 后端与非 Windows 平台没有电源计划可查询
+#[cfg(any(not(windows), feature = "mock"))]
+pub fn current_power_scheme() -> Result<PowerSchemePersonality, DeviceAwareError> {
+    Err(DeviceAwareError::Unsupported)
+}
+
+// 非 Windows 平台没有可查询的系统电源状态
+#[cfg(all(not(windows), not(feature = "mock")))]
+pub fn system_power_status() -> Result<SystemPowerStatus, DeviceAwareError> {
+    Err(DeviceAwareError::Unsupported)
+}
+
+// ============================================================================
+// mock 后端: 返回测试通过 set_system_power_status 设置的值
+// ============================================================================
+
+#[cfg(feature = "mock")]
+static MOCK_STATUS: std::sync::Mutex<Option<SystemPowerStatus>> = std::sync::Mutex::new(None);
+
+#[cfg(feature = "mock")]
+pub fn system_power_status() -> Result<SystemPowerStatus, DeviceAwareError> {
+    MOCK_STATUS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .ok_or_else(|| DeviceAwareError::Query(crate::power_observer::E_NOTIMPL.into()))
+}
+
+// 设置 mock 后端返回的状态；None 表示查询失败
+#[cfg(feature = "mock")]
+pub fn set_system_power_status(status: Option<SystemPowerStatus>) {
+    *MOCK_STATUS.lock().unwrap_or_else(|e| e.into_inner()) = status;
+}
+
+#[cfg(feature = "mock")]
+static MOCK_BATTERY: std::sync::Mutex<Option<BatteryState>> = std::sync::Mutex::new(None);
+
+#[cfg(feature = "mock")]
+pub fn battery_state() -> Result<BatteryState, DeviceAwareError> {
+    MOCK_BATTERY
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .ok_or_else(|| DeviceAwareError::Query(crate::power_observer::E_NOTIMPL.into()))
+}
+
+// 设置 mock 后端 battery_state 返回的值；None 表示查询失败
+#[cfg(feature = "mock")]
+pub fn set_battery_state(state: Option<BatteryState>) {
+    *MOCK_BATTERY.lock().unwrap_or_else(|e| e.into_inner()) = state;
+}
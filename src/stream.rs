@@ -0,0 +1,62 @@
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+use windows_core::GUID;
+
+use crate::{DeviceAwareError, EffectiveModeObserver, EffectivePowerMode, PowerSettingObserver};
+
+// ============================================================================
+// 异步 Stream 适配 (tokio feature)
+// Stream 持有观察者本身，只要 Stream 存活注册就不会被注销。
+// into_stream 直接注册并返回 Stream: 观察者的回调在注册时固定，无法把已有观察者转换过来
+// ============================================================================
+
+pub struct EffectiveModeStream {
+    rx: UnboundedReceiver<EffectivePowerMode>,
+    _observer: EffectiveModeObserver,
+}
+
+impl Stream for EffectiveModeStream {
+    type Item = EffectivePowerMode;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+pub struct PowerSettingStream {
+    rx: UnboundedReceiver<u32>,
+    _observer: PowerSettingObserver,
+}
+
+impl Stream for PowerSettingStream {
+    type Item = u32;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+impl EffectiveModeObserver {
+    // 与 new_channel 对应的异步版本
+    pub fn into_stream() -> Result<EffectiveModeStream, DeviceAwareError> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let observer = Self::new(move |mode| {
+            let _ = tx.send(mode);
+        })?;
+        Ok(EffectiveModeStream { rx, _observer: observer })
+    }
+}
+
+impl PowerSettingObserver {
+    // 与 new_channel 对应的异步版本
+    pub fn into_stream(guid: GUID) -> Result<PowerSettingStream, DeviceAwareError> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let observer = Self::new(guid, move |val| {
+            let _ = tx.send(val);
+        })?;
+        Ok(PowerSettingStream { rx, _observer: observer })
+    }
+}
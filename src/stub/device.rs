@@ -0,0 +1,41 @@
+// ============================================================================
+// 非 Windows 平台的占位后端: DeviceNotifyObserver::new 始终返回 Unsupported
+// ============================================================================
+
+use std::convert::Infallible;
+
+use windows_core::GUID;
+
+use crate::{DeviceAwareError, DeviceEvent};
+
+pub type DeviceEventCallback = Box<dyn Fn(DeviceEvent) + Send + Sync>;
+
+pub struct DeviceNotifyObserver {
+    never: Infallible,
+}
+
+impl DeviceNotifyObserver {
+    pub fn new<F>(_interface_guid: GUID, _handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(DeviceEvent) + Send + Sync + 'static
+    {
+        Err(DeviceAwareError::Unsupported)
+    }
+
+    pub fn interface_guid(&self) -> GUID {
+        match self.never {}
+    }
+
+    pub fn is_active(&self) -> bool {
+        match self.never {}
+    }
+
+    pub fn unsubscribe(&mut self) {
+        match self.never {}
+    }
+}
+
+impl Drop for DeviceNotifyObserver {
+    fn drop(&mut self) {
+        match self.never {}
+    }
+}
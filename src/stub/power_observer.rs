@@ -0,0 +1,161 @@
+// ============================================================================
+// 非 Windows 平台的占位后端: 与真实 power_observer 保持相同的公开 API，
+// 所有构造函数都返回 DeviceAwareError::Unsupported，因此不存在任何观察者实例
+// ============================================================================
+
+use std::convert::Infallible;
+use std::fmt;
+use std::sync::mpsc::Receiver;
+use std::time::{Duration, SystemTime};
+
+use windows_core::GUID;
+
+use crate::{DeviceAwareError, EffectivePowerMode};
+
+// ============================================================================
+// 1. EffectiveModeObserver (stub)
+// ============================================================================
+
+pub type EffectiveModeCallback = Box<dyn Fn(EffectivePowerMode) + Send + Sync>;
+
+pub struct EffectiveModeObserver {
+    never: Infallible,
+}
+
+impl EffectiveModeObserver {
+    pub fn new<F>(_handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(EffectivePowerMode) + Send + Sync + 'static
+    {
+        Err(DeviceAwareError::Unsupported)
+    }
+
+    pub fn new_timestamped<F>(_handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(EffectivePowerMode, SystemTime) + Send + Sync + 'static
+    {
+        Err(DeviceAwareError::Unsupported)
+    }
+
+    pub fn new_channel() -> Result<(Self, Receiver<EffectivePowerMode>), DeviceAwareError> {
+        Err(DeviceAwareError::Unsupported)
+    }
+
+    pub fn version(&self) -> u32 {
+        match self.never {}
+    }
+
+    pub fn with_slow_callback_warning(self, _threshold: Duration) -> Self {
+        match self.never {}
+    }
+
+    pub fn unsubscribe(&mut self) {
+        match self.never {}
+    }
+}
+
+impl Drop for EffectiveModeObserver {
+    fn drop(&mut self) {
+        match self.never {}
+    }
+}
+
+impl fmt::Debug for EffectiveModeObserver {
+    fn fmt(&self, _f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.never {}
+    }
+}
+
+// ============================================================================
+// 2. PowerSettingObserver (stub)
+// ============================================================================
+
+pub type PowerSettingCallback = Box<dyn Fn(u32) + Send + Sync>;
+
+pub type RawPowerSettingCallback = Box<dyn Fn(&[u8]) + Send + Sync>;
+
+pub struct PowerSettingObserver {
+    never: Infallible,
+}
+
+impl PowerSettingObserver {
+    pub fn new<F>(_guid: GUID, _handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(u32) + Send + Sync + 'static
+    {
+        Err(DeviceAwareError::Unsupported)
+    }
+
+    pub fn new_timestamped<F>(_guid: GUID, _handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(u32, SystemTime) + Send + Sync + 'static
+    {
+        Err(DeviceAwareError::Unsupported)
+    }
+
+    pub fn new_raw<F>(_guid: GUID, _handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(&[u8]) + Send + Sync + 'static
+    {
+        Err(DeviceAwareError::Unsupported)
+    }
+
+    pub fn new_channel(_guid: GUID) -> Result<(Self, Receiver<u32>), DeviceAwareError> {
+        Err(DeviceAwareError::Unsupported)
+    }
+
+    pub fn current_value(&self) -> Result<u32, DeviceAwareError> {
+        match self.never {}
+    }
+
+    pub fn guid(&self) -> GUID {
+        match self.never {}
+    }
+
+    pub fn is_active(&self) -> bool {
+        match self.never {}
+    }
+
+    pub fn pause(&self) {
+        match self.never {}
+    }
+
+    pub fn resume(&self) {
+        match self.never {}
+    }
+
+    pub fn is_paused(&self) -> bool {
+        match self.never {}
+    }
+
+    pub fn wait_for_change(&self, _timeout: Duration) -> Option<u32> {
+        match self.never {}
+    }
+
+    pub fn verify_notifications(&self, _timeout: Duration) -> bool {
+        match self.never {}
+    }
+
+    pub fn with_history(self, _capacity: usize) -> Self {
+        match self.never {}
+    }
+
+    pub fn history(&self) -> Vec<(SystemTime, u32)> {
+        match self.never {}
+    }
+
+    pub fn with_slow_callback_warning(self, _threshold: Duration) -> Self {
+        match self.never {}
+    }
+
+    pub fn unsubscribe(&mut self) {
+        match self.never {}
+    }
+}
+
+impl Drop for PowerSettingObserver {
+    fn drop(&mut self) {
+        match self.never {}
+    }
+}
+
+impl fmt::Debug for PowerSettingObserver {
+    fn fmt(&self, _f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.never {}
+    }
+}
@@ -0,0 +1,31 @@
+// ============================================================================
+// 非 Windows 平台的占位后端: SuspendResumeObserver::new 始终返回 Unsupported
+// ============================================================================
+
+use std::convert::Infallible;
+
+use crate::{DeviceAwareError, SystemPowerEvent};
+
+pub type SuspendResumeCallback = Box<dyn Fn(SystemPowerEvent) + Send + Sync>;
+
+pub struct SuspendResumeObserver {
+    never: Infallible,
+}
+
+impl SuspendResumeObserver {
+    pub fn new<F>(_handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(SystemPowerEvent) + Send + Sync + 'static
+    {
+        Err(DeviceAwareError::Unsupported)
+    }
+
+    pub fn unsubscribe(&mut self) {
+        match self.never {}
+    }
+}
+
+impl Drop for SuspendResumeObserver {
+    fn drop(&mut self) {
+        match self.never {}
+    }
+}
@@ -0,0 +1,133 @@
+use std::ffi::c_void;
+
+use windows::Win32::Foundation::HANDLE;
+use windows::Win32::System::Power::{
+    PowerRegisterSuspendResumeNotification, PowerUnregisterSuspendResumeNotification,
+    DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS, HPOWERNOTIFY,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    DEVICE_NOTIFY_CALLBACK, PBT_APMRESUMEAUTOMATIC, PBT_APMSUSPEND,
+};
+
+use crate::context::OwnedContext;
+use crate::panic_guard::guard_callback;
+use crate::reentrancy::{defer_release, enter_callback, is_in_callback, release_when_idle};
+use crate::{DeviceAwareError, SystemPowerEvent};
+
+// ============================================================================
+// 3. SuspendResumeObserver: 睡眠 / 唤醒通知
+// 与 PowerSettingObserver 相同的 DEVICE_NOTIFY_CALLBACK + Double Boxing 模式，
+// 只是改用 PowerRegisterSuspendResumeNotification 注册
+// ============================================================================
+
+pub type SuspendResumeCallback = Box<dyn Fn(SystemPowerEvent) + Send + Sync>;
+
+// 内存所有权约定与 PowerSettingObserver 相同: 上下文在注册失败时立即回滚，
+// 否则只在 unsubscribe 注销之后释放一次
+pub struct SuspendResumeObserver {
+    handle: *mut c_void,
+    context: OwnedContext<SuspendResumeCallback>,
+}
+
+impl SuspendResumeObserver {
+    pub fn new<F>(handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(SystemPowerEvent) + Send + Sync + 'static
+    {
+        let callback: SuspendResumeCallback = Box::new(handler);
+
+        // 注册失败时由 OwnedContext 回滚
+        let registered = OwnedContext::register_with(callback, |context| {
+            // params 只需在注册调用期间有效: 系统在调用内复制 Callback 与 Context，之后只保留
+            // Context 的值，不会再访问 params 本身；需要长期存活的只有 Context 指向的上下文
+            let mut params = DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS {
+                Callback: Some(Self::static_callback),
+                Context: context as *mut c_void,
+            };
+            let mut handle = std::ptr::null_mut();
+            let result = unsafe {
+                PowerRegisterSuspendResumeNotification(
+                    DEVICE_NOTIFY_CALLBACK,
+                    HANDLE(&mut params as *mut _ as *mut c_void),
+                    &mut handle,
+                )
+            };
+            result.to_hresult().ok().map(|()| handle)
+        });
+
+        let (context, handle) = match registered {
+            Ok(registered) => registered,
+            Err(e) => {
+                log_error!("PowerRegisterSuspendResumeNotification failed: {:?}", e);
+                return Err(DeviceAwareError::Registration {
+                    guid: None,
+                    api: "PowerRegisterSuspendResumeNotification",
+                    error: e,
+                });
+            }
+        };
+
+        Ok(SuspendResumeObserver {
+            handle,
+            context,
+        })
+    }
+
+    // 主动注销通知并释放回调；之后 Drop 不再做任何事，重复调用也是安全的
+    // 可以在自己的回调中调用，此时注销与释放推迟到回调返回之后
+    pub fn unsubscribe(&mut self) {
+        if is_in_callback(self.context.as_ptr()) {
+            let handle = std::mem::replace(&mut self.handle, std::ptr::null_mut()) as isize;
+            let context = self.context.take();
+            defer_release("SuspendResumeObserver", context, move || {
+                unsafe {
+                    let _ = PowerUnregisterSuspendResumeNotification(HPOWERNOTIFY(handle));
+                }
+            });
+            return;
+        }
+        if !self.handle.is_null() {
+            log_debug!("SuspendResumeObserver: unregistering suspend/resume notifications");
+            unsafe {
+                let _ = PowerUnregisterSuspendResumeNotification(HPOWERNOTIFY(self.handle as isize));
+            }
+            self.handle = std::ptr::null_mut();
+        }
+        // 等其他线程上仍在执行的回调返回后释放，随即置空，防止重复释放
+        release_when_idle(&mut self.context);
+    }
+
+    // 只转发 PBT_APMSUSPEND 与 PBT_APMRESUMEAUTOMATIC；
+    // PBT_APMRESUMESUSPEND 仅在用户交互唤醒时额外发送一次，这里忽略以免重复
+    unsafe extern "system" fn static_callback(
+        context: *const c_void,
+        type_: u32,
+        _setting: *const c_void,
+    ) -> u32 {
+        if context.is_null() {
+            return 0;
+        }
+        let event = match type_ {
+            PBT_APMSUSPEND => SystemPowerEvent::Suspend,
+            PBT_APMRESUMEAUTOMATIC => SystemPowerEvent::Resume,
+            _ => return 0,
+        };
+        log_trace!("SuspendResumeObserver: {:?}", event);
+        let callback = unsafe { OwnedContext::<SuspendResumeCallback>::get(context) };
+        enter_callback(context, || {
+            guard_callback("SuspendResumeObserver", || callback(event));
+        });
+        0
+    }
+}
+
+// SAFETY: 与 PowerSettingObserver 相同，上下文只由系统回调以只读方式使用，
+// 仅在 unsubscribe (需要 &mut self) 或 Drop 中释放一次
+unsafe impl Send for SuspendResumeObserver {}
+// SAFETY: 没有任何 &self 方法会触碰上下文或 handle
+unsafe impl Sync for SuspendResumeObserver {}
+
+impl Drop for SuspendResumeObserver {
+    fn drop(&mut self) {
+        self.unsubscribe();
+    }
+}
@@ -0,0 +1,142 @@
+use std::time::Duration;
+
+use crate::watchdog::Watchdog;
+use crate::DeviceAwareError;
+
+// ============================================================================
+// 热状态: CallNtPowerInformation(SystemPowerInformation)
+// 热区没有对应的电源通知，只能主动轮询；ThermalMonitor 在后台线程按间隔查询，
+// 每次查询结果都回调
+// ============================================================================
+
+// 系统冷却策略 (SYSTEM_POWER_INFORMATION.CoolingMode)
+// - PO_TZ_ACTIVE (0): 主动散热 (风扇)，不降频
+// - PO_TZ_PASSIVE (1): 被动散热，通过降低处理器频率控制温度
+// - PO_TZ_INVALID_MODE (2) 及其他取值: 没有热区信息
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum CoolingMode {
+    Active,
+    Passive,
+    Unknown,
+}
+
+impl From<u16> for CoolingMode {
+    fn from(val: u16) -> Self {
+        match val {
+            0 => CoolingMode::Active,
+            1 => CoolingMode::Passive,
+            _ => CoolingMode::Unknown,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ThermalState {
+    pub cooling_mode: CoolingMode,
+    // 当前处理器空闲程度 (百分比)
+    pub idleness: u32,
+    // 系统允许进入空闲的最大程度 (百分比)
+    pub max_idleness_allowed: u32,
+}
+
+impl ThermalState {
+    // 被动散热意味着系统正在通过降频控制温度
+    pub fn is_throttling(&self) -> bool {
+        self.cooling_mode == CoolingMode::Passive
+    }
+}
+
+#[cfg(all(windows, not(feature = "mock")))]
+pub fn thermal_state() -> Result<ThermalState, DeviceAwareError> {
+    use windows::Win32::System::Power::{
+        CallNtPowerInformation, SystemPowerInformation, SYSTEM_POWER_INFORMATION,
+    };
+
+    let mut info = SYSTEM_POWER_INFORMATION::default();
+    unsafe {
+        CallNtPowerInformation(
+            SystemPowerInformation,
+            None,
+            0,
+            Some(&mut info as *mut _ as *mut _),
+            std::mem::size_of::<SYSTEM_POWER_INFORMATION>() as u32,
+        )
+    }
+    .ok()
+    .map_err(DeviceAwareError::Query)?;
+
+    Ok(ThermalState {
+        cooling_mode: CoolingMode::from(info.CoolingMode.0),
+        idleness: info.Idleness,
+        max_idleness_allowed: info.MaxIdlenessAllowed,
+    })
+}
+
+// 非 Windows 平台没有可查询的热状态
+#[cfg(all(not(windows), not(feature = "mock")))]
+pub fn thermal_state() -> Result<ThermalState, DeviceAwareError> {
+    Err(DeviceAwareError::Unsupported)
+}
+
+// ============================================================================
+// mock 后端: 返回测试通过 set_thermal_state 设置的值
+// ============================================================================
+
+#[cfg(feature = "mock")]
+static MOCK_THERMAL: std::sync::Mutex<Option<ThermalState>> = std::sync::Mutex::new(None);
+
+#[cfg(feature = "mock")]
+pub fn thermal_state() -> Result<ThermalState, DeviceAwareError> {
+    MOCK_THERMAL
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .ok_or_else(|| DeviceAwareError::Query(crate::power_observer::E_NOTIMPL.into()))
+}
+
+// 设置 mock 后端返回的热状态；None 表示查询失败
+#[cfg(feature = "mock")]
+pub fn set_thermal_state(state: Option<ThermalState>) {
+    *MOCK_THERMAL.lock().unwrap_or_else(|e| e.into_inner()) = state;
+}
+
+// ============================================================================
+// ThermalMonitor: 按固定间隔轮询 thermal_state，每次查询成功都回调
+// (空闲程度几乎每次都会变化，是否忽略重复状态交给调用方决定)
+// ============================================================================
+
+pub struct ThermalMonitor {
+    interval: Duration,
+    // Drop 时结束轮询线程
+    _poller: Watchdog,
+}
+
+impl ThermalMonitor {
+    // 创建时先同步查询一次: 查询失败直接返回错误，成功则立即以当前状态回调一次
+    pub fn new<F>(interval: Duration, handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(ThermalState) + Send + Sync + 'static
+    {
+        let initial = thermal_state()?;
+        handler(initial);
+
+        let mut last_mode = initial.cooling_mode;
+        let poller = Watchdog::spawn(interval, move || match thermal_state() {
+            Ok(state) => {
+                if state.cooling_mode != last_mode {
+                    log_debug!("ThermalMonitor: cooling mode {:?} -> {:?}", last_mode, state.cooling_mode);
+                    last_mode = state.cooling_mode;
+                }
+                handler(state);
+            }
+            Err(e) => log_warn!("ThermalMonitor: query failed: {}", e),
+        });
+
+        Ok(ThermalMonitor { interval, _poller: poller })
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+}
@@ -0,0 +1,375 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::guids::{
+    GUID_ACDC_POWER_SOURCE, GUID_BATTERY_DISCHARGE_LEVEL_0, GUID_BATTERY_DISCHARGE_LEVEL_1,
+    GUID_BATTERY_PERCENTAGE_REMAINING, GUID_GLOBAL_USER_PRESENCE, GUID_POWERSCHEME_PERSONALITY,
+    GUID_POWER_SAVING_STATUS, GUID_SESSION_USER_PRESENCE,
+};
+use crate::observer_ext::decode_guid;
+use crate::{
+    current_power_scheme, system_power_status, BatteryAlarm, DeviceAwareError, PowerSchemePersonality, PowerSettingObserver, PowerSourceType,
+    UserPresence,
+};
+
+// ============================================================================
+// 常用 GUID 的类型化观察者: 内部仍是 PowerSettingObserver，只负责把 u32 转成具体类型
+// ============================================================================
+
+pub struct PowerSaverObserver {
+    inner: PowerSettingObserver,
+}
+
+impl PowerSaverObserver {
+    pub fn new<F>(handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(bool) + Send + Sync + 'static
+    {
+        let inner = PowerSettingObserver::new(GUID_POWER_SAVING_STATUS, move |val| handler(val != 0))?;
+        Ok(PowerSaverObserver { inner })
+    }
+
+    // 同步查询节电模式是否开启
+    pub fn current(&self) -> Result<bool, DeviceAwareError> {
+        self.inner.current_value().map(|val| val != 0)
+    }
+
+    pub fn inner(&self) -> &PowerSettingObserver {
+        &self.inner
+    }
+
+    pub fn inner_mut(&mut self) -> &mut PowerSettingObserver {
+        &mut self.inner
+    }
+}
+
+pub struct AcDcObserver {
+    inner: PowerSettingObserver,
+    // 最近一次通知是否为非交流供电 (电池或短期/UPS)
+    on_battery: Arc<AtomicBool>,
+}
+
+// 电池与短期/UPS 都算作 "没有接外部电源"；Unknown 无法判断，返回 None
+fn on_battery(source: PowerSourceType) -> Option<bool> {
+    match source {
+        PowerSourceType::AC => Some(false),
+        PowerSourceType::Battery | PowerSourceType::ShortTerm => Some(true),
+        PowerSourceType::Unknown => None,
+    }
+}
+
+impl AcDcObserver {
+    pub fn new<F>(handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(PowerSourceType) + Send + Sync + 'static
+    {
+        AcDcObserverBuilder::new().build(handler)
+    }
+
+    pub fn builder() -> AcDcObserverBuilder {
+        AcDcObserverBuilder::new()
+    }
+
+    // 根据最近一次通知判断是否靠电池 (或 UPS) 运行；尚未收到通知时为 false
+    pub fn is_on_battery(&self) -> bool {
+        self.on_battery.load(Ordering::Acquire)
+    }
+
+    // 同步查询当前电源类型
+    pub fn current(&self) -> Result<PowerSourceType, DeviceAwareError> {
+        self.inner.current_value().map(PowerSourceType::from)
+    }
+
+    pub fn inner(&self) -> &PowerSettingObserver {
+        &self.inner
+    }
+
+    pub fn inner_mut(&mut self) -> &mut PowerSettingObserver {
+        &mut self.inner
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct AcDcObserverBuilder {
+    startup_duplicate_filter: bool,
+}
+
+impl AcDcObserverBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // 注册后系统会立即推送一次当前值，部分机器紧接着还会再推送一次相同的值。
+    // 开启后在注册前用 system_power_status 查询当前电源类型，第一次通知照常送达，
+    // 之后与查询结果相同的重复通知被丢弃，直到出现不同的值为止。查询失败时不做过滤
+    pub fn with_startup_duplicate_filter(mut self) -> Self {
+        self.startup_duplicate_filter = true;
+        self
+    }
+
+    pub fn build<F>(self, handler: F) -> Result<AcDcObserver, DeviceAwareError>
+    where F: Fn(PowerSourceType) + Send + Sync + 'static
+    {
+        // Some(启动时的电源类型) 表示过滤仍然有效
+        let startup = self
+            .startup_duplicate_filter
+            .then(|| system_power_status().ok().map(|status| status.power_source()))
+            .flatten()
+            .filter(|source| *source != PowerSourceType::Unknown);
+        let startup = Mutex::new(startup);
+        let delivered = AtomicBool::new(false);
+
+        let cached = Arc::new(AtomicBool::new(false));
+        let c = cached.clone();
+        let inner = PowerSettingObserver::new(GUID_ACDC_POWER_SOURCE, move |val| {
+            let source = PowerSourceType::from(val);
+            if let Some(is_on_battery) = on_battery(source) {
+                c.store(is_on_battery, Ordering::Release);
+            }
+            {
+                let mut startup = startup.lock().unwrap_or_else(|e| e.into_inner());
+                if let Some(expected) = *startup {
+                    if source != expected {
+                        // 电源类型已真正变化，之后不再过滤
+                        *startup = None;
+                    } else if delivered.swap(true, Ordering::AcqRel) {
+                        log_debug!("AcDcObserver: dropped startup duplicate {:?}", source);
+                        return;
+                    }
+                }
+            }
+            handler(source)
+        })?;
+        Ok(AcDcObserver { inner, on_battery: cached })
+    }
+}
+
+// 只关心 "是否靠电池运行" 的简化版 AC/DC 观察者: 电池与短期/UPS 为 true，交流为 false
+pub struct OnBatteryObserver {
+    inner: AcDcObserver,
+}
+
+impl OnBatteryObserver {
+    pub fn new<F>(handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(bool) + Send + Sync + 'static
+    {
+        let inner = AcDcObserver::new(move |source| {
+            if let Some(is_on_battery) = on_battery(source) {
+                handler(is_on_battery);
+            }
+        })?;
+        Ok(OnBatteryObserver { inner })
+    }
+
+    pub fn is_on_battery(&self) -> bool {
+        self.inner.is_on_battery()
+    }
+
+    pub fn inner(&self) -> &AcDcObserver {
+        &self.inner
+    }
+
+    pub fn inner_mut(&mut self) -> &mut AcDcObserver {
+        &mut self.inner
+    }
+}
+
+// 电源计划个性: 载荷是 16 字节 GUID 而不是 u32，因此基于 new_decoded 构建
+pub struct PowerSchemeObserver {
+    inner: PowerSettingObserver,
+}
+
+impl PowerSchemeObserver {
+    pub fn new<F>(handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(PowerSchemePersonality) + Send + Sync + 'static
+    {
+        let inner = PowerSettingObserver::new_decoded(GUID_POWERSCHEME_PERSONALITY, decode_guid, move |guid| {
+            handler(PowerSchemePersonality::from(guid))
+        })?;
+        Ok(PowerSchemeObserver { inner })
+    }
+
+    // 同步查询当前电源计划的个性，见 current_power_scheme
+    pub fn current(&self) -> Result<PowerSchemePersonality, DeviceAwareError> {
+        current_power_scheme()
+    }
+
+    pub fn inner(&self) -> &PowerSettingObserver {
+        &self.inner
+    }
+
+    pub fn inner_mut(&mut self) -> &mut PowerSettingObserver {
+        &mut self.inner
+    }
+}
+
+// 用户在场状态。注意 GUID_GLOBAL_USER_PRESENCE 只投递给服务 (session 0)，
+// 普通桌面程序注册会成功但永远收不到通知；需要在服务中运行，
+// 或以 ServicePowerNotifications 使用服务状态句柄注册
+pub struct UserPresenceObserver {
+    inner: PowerSettingObserver,
+}
+
+impl UserPresenceObserver {
+    pub fn new<F>(handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(UserPresence) + Send + Sync + 'static
+    {
+        let inner = PowerSettingObserver::new(GUID_GLOBAL_USER_PRESENCE, move |val| {
+            handler(UserPresence::from(val))
+        })?;
+        Ok(UserPresenceObserver { inner })
+    }
+
+    pub fn inner(&self) -> &PowerSettingObserver {
+        &self.inner
+    }
+
+    pub fn inner_mut(&mut self) -> &mut PowerSettingObserver {
+        &mut self.inner
+    }
+}
+
+// 当前会话的用户在场状态 (GUID_SESSION_USER_PRESENCE)。与 UserPresenceObserver 不同，
+// 它投递给本会话中的普通程序，回调方式注册即可；锁定/解锁见 SessionObserver
+pub struct SessionPresenceObserver {
+    inner: PowerSettingObserver,
+}
+
+impl SessionPresenceObserver {
+    pub fn new<F>(handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(UserPresence) + Send + Sync + 'static
+    {
+        let inner = PowerSettingObserver::new(GUID_SESSION_USER_PRESENCE, move |val| {
+            handler(UserPresence::from(val))
+        })?;
+        Ok(SessionPresenceObserver { inner })
+    }
+
+    pub fn inner(&self) -> &PowerSettingObserver {
+        &self.inner
+    }
+
+    pub fn inner_mut(&mut self) -> &mut PowerSettingObserver {
+        &mut self.inner
+    }
+}
+
+// 把剩余电量按边界分桶，只在跨越边界时回调 (例如托盘图标只有 5 档)。
+// boundaries 是各桶的下界: 电量所在的桶号 = 不大于电量的边界个数。
+// 以 [20, 40, 60, 80] 为例，0-19% 为 0 号桶，20-39% 为 1 号桶，……，80-100% 为 4 号桶。
+// 第一次通知总会回调；之后同一桶内的变化被忽略
+pub struct BucketedBatteryObserver {
+    inner: PowerSettingObserver,
+    last_bucket: Arc<Mutex<Option<usize>>>,
+}
+
+impl BucketedBatteryObserver {
+    pub fn new<F>(boundaries: &[u32], handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(usize, u32) + Send + Sync + 'static
+    {
+        let mut boundaries = boundaries.to_vec();
+        boundaries.sort_unstable();
+        boundaries.dedup();
+
+        let last_bucket = Arc::new(Mutex::new(None));
+        let last = last_bucket.clone();
+        let inner = PowerSettingObserver::new(GUID_BATTERY_PERCENTAGE_REMAINING, move |percent| {
+            let bucket = boundaries.partition_point(|&b| b <= percent);
+            let changed = last.lock().unwrap_or_else(|e| e.into_inner()).replace(bucket) != Some(bucket);
+            // 不持锁调用用户回调
+            if changed {
+                handler(bucket, percent);
+            }
+        })?;
+        Ok(BucketedBatteryObserver { inner, last_bucket })
+    }
+
+    // 最近一次通知所在的桶；尚未收到通知时为 None
+    pub fn bucket(&self) -> Option<usize> {
+        *self.last_bucket.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn inner(&self) -> &PowerSettingObserver {
+        &self.inner
+    }
+
+    pub fn inner_mut(&mut self) -> &mut PowerSettingObserver {
+        &mut self.inner
+    }
+}
+
+// ============================================================================
+// BatteryAlarmObserver: 电量跌破低 / 严重阈值时告警
+// Windows 没有"到达阈值"的独立通知，GUID_BATTERY_DISCHARGE_LEVEL_* 只是阈值设置本身
+// (注册后会先收到当前值，用户修改设置时再次通知)。这里同时订阅阈值与剩余电量，
+// 在电量下降到阈值及以下时回调，每个级别只触发一次，电量回升到低阈值以上后复位。
+// ============================================================================
+
+#[derive(Default)]
+struct AlarmState {
+    low: Option<u32>,
+    critical: Option<u32>,
+    percent: Option<u32>,
+    fired: Option<BatteryAlarm>,
+}
+
+impl AlarmState {
+    // 根据当前电量与阈值计算应处的告警级别，返回需要新触发的告警
+    fn evaluate(&mut self) -> Option<BatteryAlarm> {
+        let percent = self.percent?;
+        let level = if self.critical.is_some_and(|c| percent <= c) {
+            Some(BatteryAlarm::Critical)
+        } else if self.low.is_some_and(|l| percent <= l) {
+            Some(BatteryAlarm::Low)
+        } else {
+            None
+        };
+
+        let newly_fired = match (self.fired, level) {
+            (_, None) => None,
+            (Some(BatteryAlarm::Critical), Some(_)) => None,
+            (Some(BatteryAlarm::Low), Some(BatteryAlarm::Low)) => None,
+            (_, Some(alarm)) => Some(alarm),
+        };
+        // 电量回到低阈值以上时复位；仍在告警区间内则记住最严重的级别
+        if level.is_none() || newly_fired.is_some() {
+            self.fired = level;
+        }
+        newly_fired
+    }
+}
+
+pub struct BatteryAlarmObserver {
+    _observers: Vec<PowerSettingObserver>,
+}
+
+impl BatteryAlarmObserver {
+    pub fn new<F>(handler: F) -> Result<Self, DeviceAwareError>
+    where F: Fn(BatteryAlarm) + Send + Sync + 'static
+    {
+        let handler = Arc::new(handler);
+        let state = Arc::new(Mutex::new(AlarmState::default()));
+
+        let subscribe = |guid, update: fn(&mut AlarmState, u32)| {
+            let handler = handler.clone();
+            let state = state.clone();
+            PowerSettingObserver::new(guid, move |val| {
+                let alarm = {
+                    let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+                    update(&mut state, val);
+                    state.evaluate()
+                };
+                // 不持锁调用用户回调
+                if let Some(alarm) = alarm {
+                    handler(alarm);
+                }
+            })
+        };
+
+        let observers = vec![
+            subscribe(GUID_BATTERY_DISCHARGE_LEVEL_0, |s, v| s.critical = Some(v))?,
+            subscribe(GUID_BATTERY_DISCHARGE_LEVEL_1, |s, v| s.low = Some(v))?,
+            subscribe(GUID_BATTERY_PERCENTAGE_REMAINING, |s, v| s.percent = Some(v))?,
+        ];
+
+        Ok(BatteryAlarmObserver { _observers: observers })
+    }
+}
@@ -0,0 +1,38 @@
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+// 后台线程每隔 interval 调用一次 tick；Drop 时通知线程退出并等待其结束
+pub(crate) struct Watchdog {
+    // 丢弃发送端即可唤醒并结束线程
+    stop: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Watchdog {
+    pub(crate) fn spawn<F>(interval: Duration, mut tick: F) -> Self
+    where F: FnMut() + Send + 'static
+    {
+        let (stop, rx) = mpsc::channel::<()>();
+        let thread = thread::Builder::new()
+            .name("device-aware-watchdog".to_string())
+            .spawn(move || {
+                while let Err(RecvTimeoutError::Timeout) = rx.recv_timeout(interval) {
+                    tick();
+                }
+            })
+            .inspect_err(|e| log_error!("Watchdog: failed to spawn thread: {:?}", e))
+            .ok();
+
+        Watchdog { stop: Some(stop), thread }
+    }
+}
+
+impl Drop for Watchdog {
+    fn drop(&mut self) {
+        self.stop.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}